@@ -0,0 +1,36 @@
+//! Compares `Connection::poll` with `Connection::poll_into` on a mock
+//! connection. Run with `cargo bench --features mock --bench poll`.
+
+use spacenav_plus::{Connection, Event, MotionEvent};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const EVENTS: i32 = 1_000_000;
+
+fn events() -> impl Iterator<Item = Event> {
+    (0..EVENTS).map(|i| Event::Motion(MotionEvent::new([i, 0, -i, 0, i, 0], 8)))
+}
+
+fn per_event(d: Duration) -> f64 {
+    d.as_nanos() as f64 / EVENTS as f64
+}
+
+fn main() {
+    let c = Connection::new_mock(events());
+    let start = Instant::now();
+    while let Some(ev) = c.poll() {
+        black_box(ev);
+    }
+    let poll = start.elapsed();
+
+    let c = Connection::new_mock(events());
+    let mut slot = Event::Motion(MotionEvent::new([0; 6], 0));
+    let start = Instant::now();
+    while c.poll_into(&mut slot) {
+        black_box(&slot);
+    }
+    let poll_into = start.elapsed();
+
+    println!("poll:      {:.1} ns/event", per_event(poll));
+    println!("poll_into: {:.1} ns/event", per_event(poll_into));
+}
@@ -0,0 +1,75 @@
+//! Probes the linked libspnav for symbols added after 0.x.
+//!
+//! libspnav-bindings only covers the 0.x API. For each newer function we
+//! try to link a tiny C program against `-lspnav`; when that works the
+//! matching `spnav_has_*` cfg is set and the wrapper in `lib` calls the
+//! real symbol, otherwise it reports `SpnavError::Unsupported`.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+const SYMBOLS: &[&str] = &[
+    "spnav_protocol",
+    "spnav_client_name",
+    "spnav_dev_name",
+    "spnav_dev_axes",
+    "spnav_dev_buttons",
+    "spnav_evmask",
+    "spnav_cfg_get_sens",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    for var in ["LIBRARY_PATH", "PKG_CONFIG_PATH", "CC"] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    let mut lib_dirs = Vec::new();
+    if let Ok(lib) = pkg_config::Config::new()
+        .cargo_metadata(false)
+        .probe("spnav")
+    {
+        println!("cargo:rustc-env=SPNAV_LIBRARY_VERSION={}", lib.version);
+        lib_dirs = lib.link_paths;
+    }
+
+    for sym in SYMBOLS {
+        let cfg = format!("{}_has_{}", "spnav", &sym["spnav_".len()..]);
+        println!("cargo:rustc-check-cfg=cfg({})", cfg);
+        if links(sym, &lib_dirs) {
+            println!("cargo:rustc-cfg={}", cfg);
+        }
+    }
+}
+
+/// Whether a program referencing `sym` links against libspnav.
+fn links(sym: &str, lib_dirs: &[PathBuf]) -> bool {
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set"));
+    let src = out_dir.join(format!("probe_{}.c", sym));
+    let exe = out_dir.join(format!("probe_{}", sym));
+    // Declared without a prototype, autoconf style, so no header is needed.
+    let program = format!("char {0}();\nint main(void) {{ return {0}(); }}\n", sym);
+    if fs::write(&src, program).is_err() {
+        return false;
+    }
+    let Ok(compiler) = cc::Build::new()
+        .cargo_metadata(false)
+        .warnings(false)
+        .try_get_compiler()
+    else {
+        return false;
+    };
+    let mut cmd: Command = compiler.to_command();
+    cmd.arg(&src).arg("-o").arg(&exe);
+    for dir in lib_dirs {
+        cmd.arg(format!("-L{}", dir.display()));
+    }
+    cmd.arg("-lspnav")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
@@ -0,0 +1,165 @@
+use crate::ButtonEvent;
+use std::collections::BTreeMap;
+
+/// Captures the next button press, for "press the button to use for X"
+/// rebinding dialogs.
+///
+/// Events fed while not capturing are ignored, as are releases, so the
+/// release of whatever button opened the dialog doesn't get captured.
+#[derive(Debug, Clone, Default)]
+pub struct BindingRecorder {
+    capturing: bool,
+    captured: Option<i32>,
+}
+
+impl BindingRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts listening for a press, forgetting any earlier capture.
+    pub fn start_capture(&mut self) {
+        self.capturing = true;
+        self.captured = None;
+    }
+
+    pub fn cancel(&mut self) {
+        self.capturing = false;
+    }
+
+    pub fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    /// Returns `true` if `ev` completed the capture.
+    pub fn feed(&mut self, ev: &ButtonEvent) -> bool {
+        if !self.capturing || !ev.press {
+            return false;
+        }
+        self.capturing = false;
+        self.captured = Some(ev.bnum);
+        true
+    }
+
+    /// The captured button number, once a press has been fed.
+    pub fn captured(&self) -> Option<i32> {
+        self.captured
+    }
+}
+
+/// Maps button numbers to app-defined actions.
+///
+/// With the `serde` feature, bindings (de)serialize as a plain map from
+/// button number to action, e.g. `{"0":"menu","1":"fit"}` in JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Bindings<A> {
+    map: BTreeMap<i32, A>,
+}
+
+impl<A> FromIterator<(i32, A)> for Bindings<A> {
+    fn from_iter<I: IntoIterator<Item = (i32, A)>>(iter: I) -> Self {
+        Bindings {
+            map: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<A> Default for Bindings<A> {
+    fn default() -> Self {
+        Bindings {
+            map: BTreeMap::new(),
+        }
+    }
+}
+
+impl<A> Bindings<A> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds `bnum` to `action`, returning the action it replaced.
+    pub fn bind(&mut self, bnum: i32, action: A) -> Option<A> {
+        self.map.insert(bnum, action)
+    }
+
+    pub fn unbind(&mut self, bnum: i32) -> Option<A> {
+        self.map.remove(&bnum)
+    }
+
+    pub fn get(&self, bnum: i32) -> Option<&A> {
+        self.map.get(&bnum)
+    }
+
+    /// The action bound to `ev`'s button. Presses and releases resolve
+    /// alike; check `ev.press` to act on one of them.
+    pub fn resolve(&self, ev: &ButtonEvent) -> Option<&A> {
+        self.get(ev.bnum)
+    }
+
+    /// Adds `overrides` on top of these bindings, replacing the action of
+    /// any button bound in both.
+    pub fn merge(&mut self, overrides: Bindings<A>) {
+        self.map.extend(overrides.map);
+    }
+
+    /// Device defaults with user overrides applied, see
+    /// [`merge`](Self::merge).
+    pub fn merged(mut defaults: Bindings<A>, overrides: Bindings<A>) -> Self {
+        defaults.merge(overrides);
+        defaults
+    }
+
+    /// Bindings in ascending button order.
+    pub fn iter(&self) -> impl Iterator<Item = (i32, &A)> + '_ {
+        self.map.iter().map(|(&b, a)| (b, a))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn button(bnum: i32, press: bool) -> ButtonEvent {
+        ButtonEvent { press, bnum }
+    }
+
+    #[test]
+    fn captures_first_press() {
+        let mut rec = BindingRecorder::new();
+        assert!(!rec.feed(&button(1, true)));
+        rec.start_capture();
+        assert!(!rec.feed(&button(1, false)));
+        assert!(rec.feed(&button(4, true)));
+        assert!(!rec.feed(&button(2, true)));
+        assert_eq!(rec.captured(), Some(4));
+
+        let mut bindings = Bindings::new();
+        bindings.bind(rec.captured().unwrap(), "fit view");
+        assert_eq!(bindings.get(4), Some(&"fit view"));
+        assert_eq!(bindings.get(2), None);
+    }
+
+    #[test]
+    fn resolve_with_overrides() {
+        let defaults: Bindings<&str> = [(0, "menu"), (1, "fit")].into_iter().collect();
+        let user: Bindings<&str> = [(1, "top view"), (5, "undo")].into_iter().collect();
+        let b = Bindings::merged(defaults, user);
+        assert_eq!(b.resolve(&button(0, true)), Some(&"menu"));
+        assert_eq!(b.resolve(&button(1, false)), Some(&"top view"));
+        assert_eq!(b.resolve(&button(5, true)), Some(&"undo"));
+        assert_eq!(b.resolve(&button(2, true)), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_as_map() {
+        let b: Bindings<String> = [(0, "menu".to_owned()), (12, "fit".to_owned())]
+            .into_iter()
+            .collect();
+        let json = serde_json::to_string(&b).unwrap();
+        assert_eq!(json, r#"{"0":"menu","12":"fit"}"#);
+        assert_eq!(serde_json::from_str::<Bindings<String>>(&json).unwrap(), b);
+    }
+}
@@ -0,0 +1,146 @@
+#[cfg(any(test, feature = "mock"))]
+use crate::{mock, Backend, Event};
+use crate::{Connection, SpnavError};
+
+/// Per-connection settings applied to events read through a [`Connection`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Options {
+    pub(crate) skip_zero_motion: bool,
+    pub(crate) client_name: Option<String>,
+    pub(crate) sensitivity: Option<f64>,
+    pub(crate) local_sensitivity: Option<f64>,
+    pub(crate) translation_gain: Option<f64>,
+    pub(crate) rotation_gain: Option<f64>,
+}
+
+impl Options {
+    /// Per-axis multipliers applied to motion read through the connection,
+    /// or `None` when no scaling is configured.
+    pub(crate) fn motion_gains(&self) -> Option<[f64; 6]> {
+        if self.local_sensitivity.is_none()
+            && self.translation_gain.is_none()
+            && self.rotation_gain.is_none()
+        {
+            return None;
+        }
+        let local = self.local_sensitivity.unwrap_or(1.0);
+        let t = local * self.translation_gain.unwrap_or(1.0);
+        let r = local * self.rotation_gain.unwrap_or(1.0);
+        Some([t, t, t, r, r, r])
+    }
+}
+
+/// Configures a [`Connection`] before opening it.
+///
+/// ```no_run
+/// let conn = spacenav_plus::Connection::builder()
+///     .skip_zero_motion(true)
+///     .open()?;
+/// # Ok::<(), spacenav_plus::SpnavError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionBuilder {
+    options: Options,
+}
+
+impl ConnectionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop motion events whose axes, extra axes included, are all zero.
+    ///
+    /// The device typically sends one such event when the cap is released,
+    /// and some apps rely on it to detect release, so these events are
+    /// forwarded unless this is enabled.
+    pub fn skip_zero_motion(mut self, skip: bool) -> Self {
+        self.options.skip_zero_motion = skip;
+        self
+    }
+
+    /// Name shown for this client by spacenav configuration tools.
+    ///
+    /// Registered right after opening. Defaults to the file stem of the
+    /// current executable. Ignored when the linked libspnav can't set
+    /// client names.
+    pub fn client_name(mut self, name: impl Into<String>) -> Self {
+        self.options.client_name = Some(name.into());
+        self
+    }
+
+    /// Sets the daemon's sensitivity on open, as a slider position mapped
+    /// by [`sensitivity_from_percent`](crate::sensitivity_from_percent).
+    pub fn sensitivity_percent(mut self, pct: f64) -> Self {
+        self.options.sensitivity = Some(crate::sensitivity_from_percent(pct));
+        self
+    }
+
+    /// Scales every motion axis by `mult` as events are read.
+    ///
+    /// Unlike [`sensitivity_percent`](Self::sensitivity_percent), which
+    /// changes the daemon setting shared by every client, this only
+    /// affects events read through this connection.
+    pub fn local_sensitivity(mut self, mult: f64) -> Self {
+        self.options.local_sensitivity = Some(mult);
+        self
+    }
+
+    /// Scales the translation axes `(x, y, z)` by `gain` as events are read.
+    ///
+    /// Gains multiply with [`local_sensitivity`](Self::local_sensitivity)
+    /// and each axis is rounded once, after both are applied. Zero-motion
+    /// filtering looks at the raw event, before any scaling.
+    pub fn translation_gain(mut self, gain: f64) -> Self {
+        self.options.translation_gain = Some(gain);
+        self
+    }
+
+    /// Scales the rotation axes `(rx, ry, rz)` by `gain` as events are read.
+    /// Composes like [`translation_gain`](Self::translation_gain).
+    pub fn rotation_gain(mut self, gain: f64) -> Self {
+        self.options.rotation_gain = Some(gain);
+        self
+    }
+
+    pub fn open(self) -> Result<Connection, SpnavError> {
+        Connection::open_with(self.options).and_then(|conn| {
+            Self::configure(&conn)?;
+            Ok(conn)
+        })
+    }
+
+    /// Like [`Connection::new_mock`], with this builder's settings.
+    #[cfg(any(test, feature = "mock"))]
+    pub fn open_mock(self, events: impl IntoIterator<Item = Event>) -> Connection {
+        self.open_backend(Backend::Mock(mock::Mock::new(events).into()))
+    }
+
+    #[cfg(any(test, feature = "mock"))]
+    pub(crate) fn open_backend(self, backend: Backend) -> Connection {
+        let conn = Connection::open_on(backend, self.options).expect("mock open is infallible");
+        Self::configure(&conn).expect("mock configuration is infallible");
+        conn
+    }
+
+    /// Applies settings that need a live connection.
+    pub(crate) fn configure(conn: &Connection) -> Result<(), SpnavError> {
+        if let Some(sens) = conn.options.sensitivity {
+            conn.set_sensitivity(sens)?;
+        }
+        let name = conn
+            .options
+            .client_name
+            .clone()
+            .or_else(default_client_name);
+        if let Some(name) = name {
+            // The name is cosmetic, so failing to register it isn't fatal.
+            let _ = conn.set_client_name(&name);
+        }
+        Ok(())
+    }
+}
+
+fn default_client_name() -> Option<String> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.file_stem()?.to_string_lossy().into_owned())
+}
@@ -0,0 +1,271 @@
+use crate::{ButtonEvent, Connection};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Tracks which buttons are currently held, as a bit mask of button
+/// numbers `0..capacity`. Events for buttons outside that range are
+/// ignored.
+///
+/// The first 64 buttons live in a single word; larger capacities add a
+/// bit vector for the rest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ButtonState {
+    capacity: usize,
+    mask: u64,
+    extra: Vec<u64>,
+    pressed_at: BTreeMap<i32, Instant>,
+}
+
+impl Default for ButtonState {
+    fn default() -> Self {
+        ButtonState::with_capacity(64)
+    }
+}
+
+impl ButtonState {
+    /// Tracks buttons `0..64`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tracks buttons `0..n`.
+    pub fn with_capacity(n: usize) -> Self {
+        ButtonState {
+            capacity: n,
+            mask: 0,
+            extra: vec![0; n.saturating_sub(64).div_ceil(64)],
+            pressed_at: BTreeMap::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The word holding `bnum` and its bit, if `bnum` is tracked.
+    fn slot(&mut self, bnum: i32) -> Option<(&mut u64, u64)> {
+        let b = usize::try_from(bnum).ok().filter(|&b| b < self.capacity)?;
+        let word = match b {
+            0..=63 => &mut self.mask,
+            _ => &mut self.extra[(b - 64) / 64],
+        };
+        Some((word, 1 << (b % 64)))
+    }
+
+    /// Like [`ButtonState::update_at`], timestamped with `Instant::now()`.
+    pub fn update(&mut self, ev: &ButtonEvent) {
+        self.update_at(ev, Instant::now());
+    }
+
+    /// Applies a press or release, recording `now` as the press time.
+    pub fn update_at(&mut self, ev: &ButtonEvent, now: Instant) {
+        let Some((word, bit)) = self.slot(ev.bnum) else {
+            return;
+        };
+        if ev.press {
+            let newly = *word & bit == 0;
+            *word |= bit;
+            if newly {
+                self.pressed_at.insert(ev.bnum, now);
+            }
+        } else {
+            *word &= !bit;
+            self.pressed_at.remove(&ev.bnum);
+        }
+    }
+
+    /// Force-releases buttons held for at least `timeout`, returning them
+    /// in ascending order.
+    ///
+    /// A lost release event (e.g. when the event queue overflows) would
+    /// otherwise leave a button held forever. Pick a timeout well above
+    /// any hold the app legitimately expects.
+    pub fn release_stale(&mut self, now: Instant, timeout: Duration) -> Vec<i32> {
+        let stale: Vec<i32> = self
+            .pressed_at
+            .iter()
+            .filter(|(_, &at)| now.saturating_duration_since(at) >= timeout)
+            .map(|(&b, _)| b)
+            .collect();
+        for &b in &stale {
+            self.update_at(
+                &ButtonEvent {
+                    press: false,
+                    bnum: b,
+                },
+                now,
+            );
+        }
+        stale
+    }
+
+    pub fn is_pressed(&self, bnum: i32) -> bool {
+        match usize::try_from(bnum) {
+            Ok(b @ 0..=63) if b < self.capacity => self.mask & (1 << b) != 0,
+            Ok(b) if b < self.capacity => self.extra[(b - 64) / 64] & (1 << (b % 64)) != 0,
+            _ => false,
+        }
+    }
+
+    /// Held buttons in ascending order.
+    pub fn pressed(&self) -> impl Iterator<Item = i32> + '_ {
+        self.pressed_at.keys().copied()
+    }
+
+    pub fn any_pressed(&self) -> bool {
+        !self.pressed_at.is_empty()
+    }
+}
+
+impl Connection {
+    /// A [`ButtonState`] sized to the device's
+    /// [`num_buttons`](Connection::num_buttons), or to 64 buttons when the
+    /// count can't be queried.
+    pub fn new_button_state(&self) -> ButtonState {
+        ButtonState::with_capacity(self.num_buttons().unwrap_or(64))
+    }
+}
+
+/// A button press presented as a key event, see [`ButtonEvent::as_key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyLike {
+    pub code: u32,
+    pub pressed: bool,
+}
+
+impl ButtonEvent {
+    /// Maps this button onto a synthetic key code `base + bnum`, so it can
+    /// be routed through an app's existing keymap.
+    ///
+    /// Pick `base` outside the range of real key codes the app handles.
+    /// Returns `None` for negative button numbers; the code wraps on
+    /// overflow.
+    pub fn as_key(&self, base: u32) -> Option<KeyLike> {
+        let bnum = u32::try_from(self.bnum).ok()?;
+        Some(KeyLike {
+            code: base.wrapping_add(bnum),
+            pressed: self.press,
+        })
+    }
+}
+
+/// Keyboard-style auto-repeat for held buttons.
+///
+/// A button that stays held for `delay` fires once, then again every
+/// `interval` until released. The initial press is not reported, since the
+/// device already delivered it as a real event. If ticks are late, a
+/// button fires at most once per tick rather than catching up.
+#[derive(Debug, Clone)]
+pub struct ButtonRepeater {
+    delay: Duration,
+    interval: Duration,
+    next_fire: BTreeMap<i32, Instant>,
+}
+
+impl ButtonRepeater {
+    pub fn new(delay: Duration, interval: Duration) -> Self {
+        ButtonRepeater {
+            delay,
+            interval,
+            next_fire: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the buttons that should repeat at `now`, in ascending order.
+    pub fn tick(&mut self, state: &ButtonState, now: Instant) -> Vec<i32> {
+        self.next_fire.retain(|&b, _| state.is_pressed(b));
+        let mut fired = Vec::new();
+        for b in state.pressed() {
+            let next = self.next_fire.entry(b).or_insert(now + self.delay);
+            if now >= *next {
+                fired.push(b);
+                *next += self.interval;
+                if *next <= now {
+                    *next = now + self.interval;
+                }
+            }
+        }
+        fired
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn button(bnum: i32, press: bool) -> ButtonEvent {
+        ButtonEvent { press, bnum }
+    }
+
+    #[test]
+    fn state_tracks_presses() {
+        let mut s = ButtonState::new();
+        s.update(&button(3, true));
+        s.update(&button(5, true));
+        s.update(&button(3, false));
+        s.update(&button(70, true));
+        assert_eq!(s.pressed().collect::<Vec<_>>(), [5]);
+        assert!(!s.is_pressed(70));
+    }
+
+    #[test]
+    fn sized_from_device() {
+        let mock = crate::mock::Mock::new(None).with_device(80, 6, 1);
+        let c = crate::ConnectionBuilder::new().open_backend(crate::Backend::Mock(mock.into()));
+        let mut s = c.new_button_state();
+        assert_eq!(s.capacity(), 80);
+        for b in [2, 64, 79, 80] {
+            s.update(&button(b, true));
+        }
+        assert_eq!(s.pressed().collect::<Vec<_>>(), [2, 64, 79]);
+        s.update(&button(64, false));
+        assert!(!s.is_pressed(64));
+        assert!(s.is_pressed(79));
+        assert_eq!(Connection::new_mock(None).new_button_state().capacity(), 64);
+    }
+
+    #[test]
+    fn button_as_key() {
+        assert_eq!(
+            button(3, true).as_key(0x1_0000),
+            Some(KeyLike {
+                code: 0x1_0003,
+                pressed: true,
+            })
+        );
+        assert!(!button(3, false).as_key(0).unwrap().pressed);
+        assert_eq!(button(-1, true).as_key(0x1_0000), None);
+    }
+
+    #[test]
+    fn stale_buttons_released() {
+        let ms = Duration::from_millis;
+        let t0 = Instant::now();
+        let mut s = ButtonState::new();
+        s.update_at(&button(1, true), t0);
+        s.update_at(&button(4, true), t0 + ms(900));
+        s.update_at(&button(1, true), t0 + ms(950));
+        assert!(s.release_stale(t0 + ms(999), ms(1000)).is_empty());
+        assert_eq!(s.release_stale(t0 + ms(1000), ms(1000)), [1]);
+        assert!(!s.is_pressed(1));
+        assert!(s.is_pressed(4));
+    }
+
+    #[test]
+    fn repeats_after_delay() {
+        let ms = Duration::from_millis;
+        let t0 = Instant::now();
+        let mut r = ButtonRepeater::new(ms(500), ms(100));
+        let mut s = ButtonState::new();
+        s.update(&button(2, true));
+        assert!(r.tick(&s, t0).is_empty());
+        assert!(r.tick(&s, t0 + ms(499)).is_empty());
+        assert_eq!(r.tick(&s, t0 + ms(500)), [2]);
+        assert!(r.tick(&s, t0 + ms(550)).is_empty());
+        assert_eq!(r.tick(&s, t0 + ms(600)), [2]);
+        s.update(&button(2, false));
+        assert!(r.tick(&s, t0 + ms(700)).is_empty());
+        s.update(&button(2, true));
+        assert!(r.tick(&s, t0 + ms(800)).is_empty());
+    }
+}
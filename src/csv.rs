@@ -0,0 +1,40 @@
+//! CSV rows for quick logging of events.
+
+use crate::{ButtonEvent, MotionEvent};
+
+/// Header matching [`MotionEvent::to_csv_row`]: the axes in
+/// [`MotionEvent::axes`] order followed by the period.
+pub const MOTION_HEADER: &str = "x,y,z,rx,ry,rz,period";
+
+/// Header matching [`ButtonEvent::to_csv_row`].
+pub const BUTTON_HEADER: &str = "bnum,press";
+
+impl MotionEvent {
+    pub fn to_csv_row(&self) -> String {
+        let [x, y, z, rx, ry, rz] = self.axes();
+        format!("{},{},{},{},{},{},{}", x, y, z, rx, ry, rz, self.period)
+    }
+}
+
+impl ButtonEvent {
+    /// `press` is written as `1` or `0`.
+    pub fn to_csv_row(&self) -> String {
+        format!("{},{}", self.bnum, self.press as i32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rows() {
+        let m = MotionEvent::new([1, -2, 3, -4, 5, -6], 16);
+        assert_eq!(m.to_csv_row(), "1,-2,3,-4,5,-6,16");
+        let b = ButtonEvent {
+            press: true,
+            bnum: 3,
+        };
+        assert_eq!(b.to_csv_row(), "3,1");
+    }
+}
@@ -0,0 +1,85 @@
+use crate::MotionEvent;
+
+/// Per-axis change in integrated position, from [`DeltaTracker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AxisDeltas(pub [i32; 6]);
+
+impl AxisDeltas {
+    /// Axes that changed, as `(axis, delta)` in axis order.
+    pub fn changed(&self) -> impl Iterator<Item = (usize, i32)> + '_ {
+        self.0.iter().copied().enumerate().filter(|&(_, d)| d != 0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == [0; 6]
+    }
+}
+
+/// Reports how far each axis's integrated position moved with each
+/// motion event.
+///
+/// Each event's values are the cap's deflection, a rate, held over the
+/// event's `period`, the same model [`Integrator`](crate::Integrator)
+/// and [`VelocityEstimator`](crate::VelocityEstimator) use. The tracker integrates `value * period_seconds` into a position
+/// per axis and reports the change in that position, rounded to whole
+/// units with the remainder carried over. So unlike the raw values, which
+/// repeat as long as the cap is held deflected, a small deflection moves
+/// an axis only every few events and an axis at rest never changes. The
+/// position starts at zero on creation and [`reset`](Self::reset).
+#[derive(Debug, Clone, Default)]
+pub struct DeltaTracker {
+    position: [f64; 6],
+}
+
+impl DeltaTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(&mut self, ev: &MotionEvent) -> AxisDeltas {
+        let dt = ev.period as f64 / 1000.0;
+        let mut deltas = [0; 6];
+        for ((d, p), v) in deltas.iter_mut().zip(&mut self.position).zip(ev.axes()) {
+            let before = p.round();
+            *p += v as f64 * dt;
+            *d = (p.round() - before) as i32;
+        }
+        AxisDeltas(deltas)
+    }
+
+    /// The integrated position of each axis.
+    pub fn position(&self) -> [f64; 6] {
+        self.position
+    }
+
+    pub fn reset(&mut self) {
+        self.position = [0.0; 6];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reports_position_changes() {
+        let mut t = DeltaTracker::new();
+        // 100 counts for 10 ms moves x by one unit per event; 30 counts
+        // on rz moves it about every third event.
+        let m = MotionEvent::new([100, 0, 0, 0, 0, 30], 10);
+        let deltas: Vec<AxisDeltas> = (0..4).map(|_| t.feed(&m)).collect();
+        assert_eq!(
+            deltas,
+            [
+                AxisDeltas([1, 0, 0, 0, 0, 0]),
+                AxisDeltas([1, 0, 0, 0, 0, 1]),
+                AxisDeltas([1, 0, 0, 0, 0, 0]),
+                AxisDeltas([1, 0, 0, 0, 0, 0]),
+            ]
+        );
+        assert!(t.feed(&MotionEvent::new([0; 6], 10)).is_empty());
+        let m = MotionEvent::new([-300, 0, 0, 0, 0, 0], 10);
+        assert_eq!(t.feed(&m).changed().collect::<Vec<_>>(), [(0, -3)]);
+        assert!((t.position()[0] - 1.0).abs() < 1e-9);
+    }
+}
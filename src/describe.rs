@@ -0,0 +1,117 @@
+use crate::{DeviceModel, Event, MotionEvent};
+
+/// Phrases for each axis as `(positive, negative)`, in spacenavd's
+/// default frame: x right, y up, z into the screen, left-handed. So
+/// pushing the cap away from the user is `+z`, and each rotation is
+/// positive when it turns clockwise seen from the positive end of its
+/// axis: tilting the top forward is `+rx` and twisting clockwise seen
+/// from above is `+ry`. Apps that want a right-handed frame, like
+/// Blender's NDOF handling, negate `z` and the rotations.
+///
+/// The rotation axes are named as in [`Gesture`](crate::Gesture): `rx`
+/// tilts the cap, `ry` spins it (phrased as rotating) and `rz` rolls it.
+const AXIS_PHRASES: [(&str, &str); 6] = [
+    ("move right", "move left"),
+    ("lift up", "push down"),
+    ("push forward", "pull back"),
+    ("tilt forward", "tilt back"),
+    ("rotate clockwise", "rotate counterclockwise"),
+    ("roll left", "roll right"),
+];
+
+impl MotionEvent {
+    /// Index of the axis with the largest magnitude, or `None` when all
+    /// are zero. Ties go to the lower index, so translation wins over
+    /// rotation.
+    pub fn dominant_axis(&self) -> Option<usize> {
+        let axes = self.axes();
+        let (i, v) = axes
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, v)| v.unsigned_abs())?;
+        (*v != 0).then_some(i)
+    }
+}
+
+impl Event {
+    /// Describes the event in words, e.g. for voice feedback.
+    ///
+    /// Motion is named after its [dominant axis](MotionEvent::dominant_axis)
+    /// and sign: "tilt forward", "rotate clockwise" (seen from above) and
+    /// so on, or "no motion" when every axis is zero. Buttons are described
+    /// by number, e.g. "button 3 pressed"; use
+    /// [`describe_for`](Self::describe_for) for the model's labels.
+    pub fn describe(&self) -> String {
+        self.describe_for(&DeviceModel::Unknown(String::new()))
+    }
+
+    /// Like [`describe`](Self::describe), naming buttons with `model`'s
+    /// labels where it has one, e.g. "button menu pressed".
+    pub fn describe_for(&self, model: &DeviceModel) -> String {
+        match self {
+            Event::Motion(m) => match m.dominant_axis() {
+                Some(i) => {
+                    let (pos, neg) = AXIS_PHRASES[i];
+                    if m.axes()[i] > 0 { pos } else { neg }.to_owned()
+                }
+                None => "no motion".to_owned(),
+            },
+            Event::Button(b) => {
+                let state = if b.press { "pressed" } else { "released" };
+                match model.button_name(b.bnum) {
+                    Some(name) => format!("button {} {}", name, state),
+                    None => format!("button {} {}", b.bnum, state),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ButtonEvent;
+
+    fn motion(axes: [i32; 6]) -> Event {
+        Event::Motion(MotionEvent::new(axes, 8))
+    }
+
+    #[test]
+    fn describes_dominant_motion() {
+        assert_eq!(motion([10, 0, 0, -200, 30, 0]).describe(), "tilt back");
+        assert_eq!(
+            motion([0, 0, 5, 0, -90, 0]).describe(),
+            "rotate counterclockwise"
+        );
+        assert_eq!(motion([-40, 0, 0, 40, 0, 0]).describe(), "move left");
+        assert_eq!(motion([0, 0, 0, 0, 0, -50]).describe(), "roll right");
+        assert_eq!(motion([0; 6]).describe(), "no motion");
+    }
+
+    #[test]
+    fn follows_spacenavd_frame() {
+        // spacenavd reports a left-handed frame with z into the screen,
+        // which is why Blender's NDOF code negates z and the rotations
+        // to get its right-handed one.
+        assert_eq!(motion([0, 0, 120, 0, 0, 0]).describe(), "push forward");
+        assert_eq!(motion([0, 0, 0, 0, 120, 0]).describe(), "rotate clockwise");
+    }
+
+    #[test]
+    fn describes_buttons() {
+        let b = Event::Button(ButtonEvent {
+            press: true,
+            bnum: 0,
+        });
+        assert_eq!(
+            b.describe_for(&DeviceModel::SpaceMousePro),
+            "button menu pressed"
+        );
+        let b = Event::Button(ButtonEvent {
+            press: false,
+            bnum: 3,
+        });
+        assert_eq!(b.describe(), "button 3 released");
+    }
+}
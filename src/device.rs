@@ -0,0 +1,216 @@
+use crate::{ButtonEvent, SpnavError};
+use std::path::PathBuf;
+
+/// Known space mouse models.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceModel {
+    SpaceNavigator,
+    SpaceMouseCompact,
+    SpaceMouseWireless,
+    SpaceMousePro,
+    SpaceExplorer,
+    SpacePilot,
+    SpacePilotPro,
+    /// A device this crate doesn't recognise, with its reported name.
+    Unknown(String),
+}
+
+const TWO_BUTTONS: &[&str] = &["left", "right"];
+
+const SPACEMOUSE_PRO_BUTTONS: &[&str] = &[
+    "menu", "fit", "top", "right", "front", "roll", "1", "2", "3", "4", "esc", "alt", "shift",
+    "ctrl", "lock",
+];
+
+const SPACE_EXPLORER_BUTTONS: &[&str] = &[
+    "1", "2", "top", "left", "right", "front", "esc", "alt", "shift", "ctrl", "fit", "panel", "+",
+    "-", "2d",
+];
+
+impl DeviceModel {
+    const ALL: &'static [DeviceModel] = &[
+        DeviceModel::SpaceNavigator,
+        DeviceModel::SpaceMouseCompact,
+        DeviceModel::SpaceMouseWireless,
+        DeviceModel::SpaceMousePro,
+        DeviceModel::SpaceExplorer,
+        DeviceModel::SpacePilot,
+        DeviceModel::SpacePilotPro,
+    ];
+
+    /// Short identifier used as the prefix of button ids.
+    pub fn slug(&self) -> &'static str {
+        match self {
+            DeviceModel::SpaceNavigator => "spacenav",
+            DeviceModel::SpaceMouseCompact => "spacemouse-compact",
+            DeviceModel::SpaceMouseWireless => "spacemouse-wireless",
+            DeviceModel::SpaceMousePro => "spacemouse-pro",
+            DeviceModel::SpaceExplorer => "space-explorer",
+            DeviceModel::SpacePilot => "spacepilot",
+            DeviceModel::SpacePilotPro => "spacepilot-pro",
+            DeviceModel::Unknown(_) => "unknown",
+        }
+    }
+
+    pub fn from_slug(slug: &str) -> Option<DeviceModel> {
+        DeviceModel::ALL.iter().find(|m| m.slug() == slug).cloned()
+    }
+
+    /// Button labels indexed by `bnum`, in spacenavd's numbering.
+    fn buttons(&self) -> &'static [&'static str] {
+        match self {
+            DeviceModel::SpaceNavigator
+            | DeviceModel::SpaceMouseCompact
+            | DeviceModel::SpaceMouseWireless => TWO_BUTTONS,
+            DeviceModel::SpaceMousePro => SPACEMOUSE_PRO_BUTTONS,
+            DeviceModel::SpaceExplorer => SPACE_EXPLORER_BUTTONS,
+            _ => &[],
+        }
+    }
+
+    pub fn button_name(&self, bnum: i32) -> Option<&'static str> {
+        usize::try_from(bnum)
+            .ok()
+            .and_then(|i| self.buttons().get(i).copied())
+    }
+
+    pub fn button_number(&self, name: &str) -> Option<i32> {
+        self.buttons()
+            .iter()
+            .position(|n| *n == name)
+            .map(|i| i as i32)
+    }
+}
+
+/// Recognises a model from the device name reported by the daemon, such as
+/// `"3Dconnexion SpaceMouse Pro"`. Matching ignores case and spaces.
+pub fn classify_model(name: &str) -> DeviceModel {
+    let key: String = name
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+    // More specific names come first, e.g. "spacepilotpro" before "spacepilot".
+    const PATTERNS: &[(&str, DeviceModel)] = &[
+        ("spacenavigator", DeviceModel::SpaceNavigator),
+        ("spacemousecompact", DeviceModel::SpaceMouseCompact),
+        ("spacemousepro", DeviceModel::SpaceMousePro),
+        ("spacemousewireless", DeviceModel::SpaceMouseWireless),
+        ("spaceexplorer", DeviceModel::SpaceExplorer),
+        ("spacepilotpro", DeviceModel::SpacePilotPro),
+        ("spacepilot", DeviceModel::SpacePilot),
+    ];
+    PATTERNS
+        .iter()
+        .find(|(pat, _)| key.contains(pat))
+        .map(|(_, model)| model.clone())
+        .unwrap_or_else(|| DeviceModel::Unknown(name.to_owned()))
+}
+
+/// A device the spacenav daemon can talk to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// Product name as reported by the device.
+    pub name: String,
+    /// Device node the daemon reads it from, e.g. `/dev/input/event5`.
+    pub path: PathBuf,
+}
+
+/// Lists the devices available before connecting.
+///
+/// spacenavd drives a single device and libspnav offers no way to
+/// enumerate or choose one, so this currently always fails with
+/// [`SpnavError::Unsupported`]; use [`Connection::new`](crate::Connection::new)
+/// to connect to the daemon's device.
+pub fn list_devices() -> Result<Vec<DeviceInfo>, SpnavError> {
+    Err(SpnavError::Unsupported)
+}
+
+impl ButtonEvent {
+    /// Stable identifier for this button, suitable for config files.
+    ///
+    /// Buttons `model` has a label for become `"<slug>:<label>"`, e.g.
+    /// `"spacemouse-pro:menu"`; anything else becomes `"button:<bnum>"`.
+    /// [`button_from_id`] parses either form.
+    pub fn id_string(&self, model: DeviceModel) -> String {
+        match model.button_name(self.bnum) {
+            Some(name) => format!("{}:{}", model.slug(), name),
+            None => format!("button:{}", self.bnum),
+        }
+    }
+}
+
+/// Parses an id produced by [`ButtonEvent::id_string`].
+///
+/// `"button:<bnum>"` ids carry no model and yield an unnamed
+/// [`DeviceModel::Unknown`].
+pub fn button_from_id(id: &str) -> Option<(DeviceModel, i32)> {
+    let (prefix, rest) = id.split_once(':')?;
+    if prefix == "button" {
+        return rest
+            .parse()
+            .ok()
+            .map(|n| (DeviceModel::Unknown(String::new()), n));
+    }
+    let model = DeviceModel::from_slug(prefix)?;
+    let bnum = model.button_number(rest)?;
+    Some((model, bnum))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn named_button_round_trip() {
+        let b = ButtonEvent {
+            press: true,
+            bnum: 0,
+        };
+        let id = b.id_string(DeviceModel::SpaceMousePro);
+        assert_eq!(id, "spacemouse-pro:menu");
+        assert_eq!(button_from_id(&id), Some((DeviceModel::SpaceMousePro, 0)));
+    }
+
+    #[test]
+    fn unknown_button_round_trip() {
+        let b = ButtonEvent {
+            press: false,
+            bnum: 7,
+        };
+        let id = b.id_string(DeviceModel::SpaceNavigator);
+        assert_eq!(id, "button:7");
+        assert_eq!(
+            button_from_id(&id),
+            Some((DeviceModel::Unknown(String::new()), 7))
+        );
+    }
+
+    #[test]
+    fn classifies_known_names() {
+        assert_eq!(
+            classify_model("3Dconnexion SpaceNavigator"),
+            DeviceModel::SpaceNavigator
+        );
+        assert_eq!(
+            classify_model("3Dconnexion SpaceMouse Pro Wireless"),
+            DeviceModel::SpaceMousePro
+        );
+        assert_eq!(
+            classify_model("3Dconnexion SpacePilot PRO"),
+            DeviceModel::SpacePilotPro
+        );
+        assert_eq!(classify_model("SpacePilot"), DeviceModel::SpacePilot);
+        assert_eq!(
+            classify_model("Magellan"),
+            DeviceModel::Unknown("Magellan".to_owned())
+        );
+    }
+
+    #[test]
+    fn malformed_ids() {
+        assert_eq!(button_from_id("menu"), None);
+        assert_eq!(button_from_id("spacenav:menu"), None);
+        assert_eq!(button_from_id("button:x"), None);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{ButtonEvent, Connection, Event, MotionEvent, SpnavError};
+
+/// Builds an event handler for [`Connection::run`] from one closure per
+/// event kind, so callers don't have to match on [`Event`] themselves.
+///
+/// Either closure may be left out to ignore that kind of event.
+///
+/// ```no_run
+/// use spacenav_plus::{spacenav_dispatch, Connection};
+///
+/// let conn = Connection::new()?;
+/// let err = conn.run(spacenav_dispatch! {
+///     on_motion: |m| println!("moved {:?} {:?}", m.t(), m.r()),
+///     on_button: |b| println!("button {} {}", b.bnum, b.press),
+/// });
+/// eprintln!("stopped: {}", err);
+/// # Ok::<(), spacenav_plus::SpnavError>(())
+/// ```
+#[macro_export]
+macro_rules! spacenav_dispatch {
+    (on_motion: $motion:expr, on_button: $button:expr $(,)?) => {
+        $crate::dispatch($motion, $button)
+    };
+    (on_button: $button:expr, on_motion: $motion:expr $(,)?) => {
+        $crate::dispatch($motion, $button)
+    };
+    (on_motion: $motion:expr $(,)?) => {
+        $crate::dispatch($motion, |_| {})
+    };
+    (on_button: $button:expr $(,)?) => {
+        $crate::dispatch(|_| {}, $button)
+    };
+}
+
+/// Combines per-kind handlers into one `FnMut(Event)`; see
+/// [`spacenav_dispatch!`].
+pub fn dispatch(
+    mut on_motion: impl FnMut(MotionEvent),
+    mut on_button: impl FnMut(ButtonEvent),
+) -> impl FnMut(Event) {
+    move |event| match event {
+        Event::Motion(m) => on_motion(m),
+        Event::Button(b) => on_button(b),
+    }
+}
+
+impl Connection {
+    /// Passes every event to `handler` until `wait` fails, and returns
+    /// that error.
+    ///
+    /// With the `tracing` feature the loop runs in a debug-level `run`
+    /// span, and each call to `handler` in a trace-level `event` span
+    /// carrying the event's kind and period.
+    pub fn run(&self, mut handler: impl FnMut(Event)) -> SpnavError {
+        #[cfg(feature = "tracing")]
+        let _run = tracing::debug_span!("run").entered();
+        loop {
+            match self.wait() {
+                Ok(event) => {
+                    #[cfg(feature = "tracing")]
+                    let _span = {
+                        let span = crate::trace::event_span!("event");
+                        crate::trace::record(&span, &event);
+                        span.entered()
+                    };
+                    handler(event)
+                }
+                Err(e) => return e,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dispatches_by_kind() {
+        let c = Connection::new_mock([
+            Event::Motion(MotionEvent::new([1, 0, 0, 0, 0, 0], 8)),
+            Event::Button(ButtonEvent {
+                press: true,
+                bnum: 2,
+            }),
+            Event::Motion(MotionEvent::new([3, 0, 0, 0, 0, 0], 8)),
+        ]);
+        let mut xs = Vec::new();
+        let mut buttons = Vec::new();
+        let err = c.run(crate::spacenav_dispatch! {
+            on_button: |b| buttons.push(b.bnum),
+            on_motion: |m| xs.push(m.x),
+        });
+        assert_eq!(err, SpnavError::Wait);
+        assert_eq!(xs, [1, 3]);
+        assert_eq!(buttons, [2]);
+    }
+}
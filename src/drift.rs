@@ -0,0 +1,71 @@
+use crate::MotionEvent;
+
+/// Estimates the resting bias of each axis.
+///
+/// Feed it the motion events from a window during which the device is
+/// known to be untouched, e.g. the first seconds after connecting or
+/// while the user is asked to let go; the bias is the running mean of each
+/// axis over those events. Events fed while the cap is being used skew the
+/// estimate, so the caller decides when the device is at rest. A large
+/// bias means the device drifts, which a UI can warn about or a deadzone
+/// can be sized to cover.
+#[derive(Debug, Clone, Default)]
+pub struct DriftMonitor {
+    mean: [f64; 6],
+    samples: u64,
+}
+
+impl DriftMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(&mut self, ev: &MotionEvent) {
+        self.samples += 1;
+        let n = self.samples as f64;
+        for (m, v) in self.mean.iter_mut().zip(ev.axes()) {
+            *m += (v as f64 - *m) / n;
+        }
+    }
+
+    /// Mean value of each axis in counts, zero before any event is fed.
+    pub fn bias(&self) -> [f64; 6] {
+        self.mean
+    }
+
+    /// Number of events the bias is averaged over.
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn zero_centered_noise_converges() {
+        // Small LCG so the noise is reproducible.
+        let mut seed = 12345u32;
+        let mut noise = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            ((seed >> 16) % 41) as i32 - 20
+        };
+        let mut d = DriftMonitor::new();
+        assert_eq!(d.bias(), [0.0; 6]);
+        for _ in 0..5000 {
+            d.feed(&MotionEvent::new(
+                [noise(), noise(), noise(), noise(), noise(), noise()],
+                16,
+            ));
+        }
+        assert_eq!(d.samples(), 5000);
+        assert!(d.bias().iter().all(|b| b.abs() < 1.0), "{:?}", d.bias());
+        d.reset();
+        assert_eq!((d.bias(), d.samples()), ([0.0; 6], 0));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{Deadzone, FullScale, MotionEvent, MotionTransform, ResponseCurve};
+#[cfg(feature = "serde")]
+use std::io::{self, Read, Write};
+
+/// The usual feel settings bundled together.
+///
+/// [`apply`](Self::apply) runs them in this order:
+/// 1. a [`Deadzone`] of `deadzone` counts on every axis, which may drop
+///    the event,
+/// 2. a [`ResponseCurve`] with exponent `exponent`, normalized by
+///    `full_scale`,
+/// 3. `translation_gain` on `(x, y, z)` and `rotation_gain` on
+///    `(rx, ry, rz)`.
+///
+/// The default profile changes nothing.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FeelProfile {
+    pub deadzone: i32,
+    pub exponent: f64,
+    pub translation_gain: f64,
+    pub rotation_gain: f64,
+    pub full_scale: FullScale,
+}
+
+impl Default for FeelProfile {
+    fn default() -> Self {
+        FeelProfile {
+            deadzone: 0,
+            exponent: 1.0,
+            translation_gain: 1.0,
+            rotation_gain: 1.0,
+            full_scale: FullScale::default(),
+        }
+    }
+}
+
+impl FeelProfile {
+    /// Soft response for fine positioning: a small dead zone, a strong
+    /// curve and reduced rotation.
+    pub fn precise() -> Self {
+        FeelProfile {
+            deadzone: 15,
+            exponent: 2.0,
+            translation_gain: 0.75,
+            rotation_gain: 0.5,
+            full_scale: FullScale::default(),
+        }
+    }
+
+    /// Quick response for navigating large scenes.
+    pub fn fast() -> Self {
+        FeelProfile {
+            deadzone: 5,
+            exponent: 1.2,
+            translation_gain: 2.0,
+            rotation_gain: 1.5,
+            full_scale: FullScale::default(),
+        }
+    }
+
+    /// Applies the profile, returning `None` if the dead zone drops the
+    /// event.
+    pub fn apply(&self, ev: MotionEvent) -> Option<MotionEvent> {
+        let mut ev = Deadzone::new(self.deadzone).transform(ev)?;
+        ev = ResponseCurve::new(self.exponent)
+            .with_full_scale(self.full_scale)
+            .transform(ev)?;
+        let (t, r) = (self.translation_gain, self.rotation_gain);
+        ev.scale_axes([t, t, t, r, r, r]);
+        Some(ev)
+    }
+
+    /// Reads a profile saved by [`save`](Self::save). Missing fields keep
+    /// their default.
+    #[cfg(feature = "serde")]
+    pub fn load(r: impl Read) -> io::Result<Self> {
+        Ok(serde_json::from_reader(r)?)
+    }
+
+    /// Writes the profile as JSON.
+    #[cfg(feature = "serde")]
+    pub fn save(&self, w: impl Write) -> io::Result<()> {
+        Ok(serde_json::to_writer_pretty(w, self)?)
+    }
+}
+
+/// As a transform, a learning `full_scale` also
+/// [observes](FullScale::observe) each event before it is applied.
+impl MotionTransform for FeelProfile {
+    fn transform(&mut self, ev: MotionEvent) -> Option<MotionEvent> {
+        self.full_scale.observe(&ev);
+        self.apply(ev)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Pipeline;
+
+    #[test]
+    fn matches_individual_transforms() {
+        let profile = FeelProfile::precise();
+        let mut p = Pipeline::new()
+            .with(Deadzone::new(profile.deadzone))
+            .with(ResponseCurve::new(profile.exponent));
+        for axes in [[10, 200, -350, 0, 14, -120], [3, 0, 0, -9, 0, 0], [0; 6]] {
+            let expected = p.apply(MotionEvent::new(axes, 8)).map(|mut m| {
+                m.scale_axes([0.75, 0.75, 0.75, 0.5, 0.5, 0.5]);
+                m.axes()
+            });
+            assert_eq!(
+                profile.apply(MotionEvent::new(axes, 8)).map(|m| m.axes()),
+                expected
+            );
+        }
+        assert!(profile
+            .apply(MotionEvent::new([3, 0, 0, -9, 0, 0], 8))
+            .is_none());
+    }
+
+    #[test]
+    fn curve_uses_full_scale() {
+        let profile = FeelProfile {
+            exponent: 2.0,
+            ..FeelProfile::default()
+        };
+        let ev = MotionEvent::new([175, 0, 0, 0, 0, 0], 8);
+        assert_eq!(profile.apply(ev.clone()).unwrap().x, 88);
+        let profile = FeelProfile {
+            full_scale: FullScale::new(175.0),
+            ..profile
+        };
+        assert_eq!(profile.apply(ev.clone()).unwrap().x, 175);
+        let mut learning = FeelProfile {
+            full_scale: FullScale::learning(100.0),
+            ..profile
+        };
+        assert_eq!(learning.transform(ev).unwrap().x, 175);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn load_save_round_trip() {
+        let mut buf = Vec::new();
+        FeelProfile::fast().save(&mut buf).unwrap();
+        assert_eq!(FeelProfile::load(&buf[..]).unwrap(), FeelProfile::fast());
+        let partial = FeelProfile::load(&br#"{"deadzone": 20}"#[..]).unwrap();
+        assert_eq!(partial.deadzone, 20);
+        assert_eq!(partial.exponent, 1.0);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::MotionEvent;
+use std::f64::consts::PI;
+
+/// One-euro filter (Casiez et al.) applied independently to each axis.
+///
+/// `min_cutoff` (Hz) sets how strongly a still device is smoothed; `beta`
+/// raises the cutoff as the axis speeds up, trading jitter for lag. The
+/// event `period` is used as the sample interval.
+#[derive(Debug, Clone)]
+pub struct OneEuroFilter {
+    min_cutoff: f64,
+    beta: f64,
+    d_cutoff: f64,
+    state: Option<[(f64, f64); 6]>,
+}
+
+fn alpha(cutoff: f64, dt: f64) -> f64 {
+    let tau = 1.0 / (2.0 * PI * cutoff);
+    1.0 / (1.0 + tau / dt)
+}
+
+impl OneEuroFilter {
+    pub fn new(min_cutoff: f64, beta: f64) -> Self {
+        OneEuroFilter {
+            min_cutoff,
+            beta,
+            d_cutoff: 1.0,
+            state: None,
+        }
+    }
+
+    pub fn apply(&mut self, ev: &MotionEvent) -> MotionEvent {
+        let axes = ev.axes().map(f64::from);
+        // A zero period would divide by zero below; treat it as 1ms.
+        let dt = ev.period.max(1) as f64 / 1000.0;
+        let state = match self.state.as_mut() {
+            None => self.state.insert(axes.map(|x| (x, 0.0))),
+            Some(state) => {
+                for (i, (x_hat, dx_hat)) in state.iter_mut().enumerate() {
+                    let dx = (axes[i] - *x_hat) / dt;
+                    *dx_hat += alpha(self.d_cutoff, dt) * (dx - *dx_hat);
+                    let cutoff = self.min_cutoff + self.beta * dx_hat.abs();
+                    *x_hat += alpha(cutoff, dt) * (axes[i] - *x_hat);
+                }
+                state
+            }
+        };
+        let mut out = ev.clone();
+        for (i, (x_hat, _)) in state.iter().enumerate() {
+            out.set_axis(i, x_hat.round() as i32);
+        }
+        out
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn motion(x: i32) -> MotionEvent {
+        MotionEvent::new([x, 0, 0, 0, 0, 0], 8)
+    }
+
+    fn spread(xs: &[i32]) -> i32 {
+        xs.iter().max().unwrap() - xs.iter().min().unwrap()
+    }
+
+    #[test]
+    fn reduces_jitter() {
+        let mut f = OneEuroFilter::new(1.0, 0.001);
+        let input: Vec<i32> = (0..200)
+            .map(|i| 100 + if i % 2 == 0 { 20 } else { -20 })
+            .collect();
+        let output: Vec<i32> = input.iter().map(|&x| f.apply(&motion(x)).x).collect();
+        assert!(spread(&output[100..]) < spread(&input[100..]) / 4);
+    }
+
+    #[test]
+    fn low_lag_on_step() {
+        let step = |beta| {
+            let mut f = OneEuroFilter::new(1.0, beta);
+            for _ in 0..10 {
+                f.apply(&motion(0));
+            }
+            (0..5).map(|_| f.apply(&motion(300)).x).last().unwrap()
+        };
+        assert!(step(0.05) > 250);
+        assert!(step(0.0) < 100);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{Connection, Event, EventSource, SpnavError};
+use std::io::{self, Read, Write};
+
+/// Frames larger than this are rejected as corrupt.
+const MAX_FRAME: usize = 64 * 1024;
+
+/// Writes `ev` as one frame: a big-endian `u32` byte length followed by
+/// the event as JSON, e.g. `{"type":"button","press":true,"bnum":0}`.
+pub fn write_frame(mut w: impl Write, ev: &Event) -> io::Result<()> {
+    let body = serde_json::to_vec(ev)?;
+    w.write_all(&(body.len() as u32).to_be_bytes())?;
+    w.write_all(&body)
+}
+
+/// Sends events read from a [`Connection`] to a stream, usually a
+/// `UnixStream` or `TcpStream`, for a [`RemoteSource`] at the other end.
+#[derive(Debug)]
+pub struct Forwarder<W> {
+    conn: Connection,
+    out: W,
+}
+
+impl<W: Write> Forwarder<W> {
+    pub fn new(conn: Connection, out: W) -> Self {
+        Forwarder { conn, out }
+    }
+
+    /// Forwards every queued event without blocking, returning how many
+    /// were sent.
+    pub fn pump(&mut self) -> io::Result<usize> {
+        let mut sent = 0;
+        while let Some(ev) = self.conn.poll() {
+            write_frame(&mut self.out, &ev)?;
+            sent += 1;
+        }
+        self.out.flush()?;
+        Ok(sent)
+    }
+
+    /// Forwards events as they arrive until writing fails or the
+    /// connection's `wait` does, which is reported as an
+    /// [`io::ErrorKind::Other`] error wrapping the [`SpnavError`].
+    pub fn run(&mut self) -> io::Result<()> {
+        loop {
+            let ev = self.conn.wait().map_err(io::Error::other)?;
+            write_frame(&mut self.out, &ev)?;
+            self.out.flush()?;
+        }
+    }
+
+    pub fn into_inner(self) -> (Connection, W) {
+        (self.conn, self.out)
+    }
+}
+
+/// Reads events sent by a [`Forwarder`].
+///
+/// `wait` needs a blocking stream and `poll` a non-blocking one (see
+/// `UnixStream::set_nonblocking`); `poll` on a blocking stream blocks
+/// until a frame arrives. Partial frames are buffered between calls. Once
+/// the stream ends or fails, `wait` reports [`SpnavError::Wait`] and
+/// `poll` returns `None`. A frame that doesn't decode, e.g. an event type
+/// this build doesn't know, is skipped after failing the call that read
+/// it the same way.
+#[derive(Debug)]
+pub struct RemoteSource<R> {
+    input: R,
+    buf: Vec<u8>,
+}
+
+impl<R: Read> RemoteSource<R> {
+    pub fn new(input: R) -> Self {
+        RemoteSource {
+            input,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Reads until a whole frame is buffered and decodes it.
+    fn read_event(&mut self) -> io::Result<Event> {
+        loop {
+            if let Some(ev) = self.take_frame()? {
+                return Ok(ev);
+            }
+            let mut chunk = [0; 512];
+            match self.input.read(&mut chunk)? {
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                n => self.buf.extend_from_slice(&chunk[..n]),
+            }
+        }
+    }
+
+    fn take_frame(&mut self) -> io::Result<Option<Event>> {
+        let Some(header) = self.buf.first_chunk::<4>() else {
+            return Ok(None);
+        };
+        let len = u32::from_be_bytes(*header) as usize;
+        if len > MAX_FRAME {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "oversized event frame",
+            ));
+        }
+        if self.buf.len() < 4 + len {
+            return Ok(None);
+        }
+        // Drop the frame before decoding so a bad one fails once instead
+        // of blocking every frame behind it.
+        let frame: Vec<u8> = self.buf.drain(..4 + len).skip(4).collect();
+        Ok(Some(serde_json::from_slice(&frame)?))
+    }
+}
+
+impl<R: Read> EventSource for RemoteSource<R> {
+    fn poll(&mut self) -> Option<Event> {
+        self.read_event().ok()
+    }
+
+    fn wait(&mut self) -> Result<Event, SpnavError> {
+        self.read_event().map_err(|_| SpnavError::Wait)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ButtonEvent, MotionEvent};
+    use std::os::unix::net::UnixStream;
+
+    #[test]
+    fn loopback_over_unix_socket() {
+        let (a, b) = UnixStream::pair().unwrap();
+        let conn = Connection::new_mock([
+            Event::Motion(MotionEvent::new([1, 2, 3, 4, 5, 6], 16)),
+            Event::Button(ButtonEvent {
+                press: true,
+                bnum: 7,
+            }),
+        ]);
+        let mut fwd = Forwarder::new(conn, a);
+        assert_eq!(fwd.pump().unwrap(), 2);
+        drop(fwd);
+
+        let mut remote = RemoteSource::new(b);
+        match remote.wait() {
+            Ok(Event::Motion(m)) => assert_eq!((m.axes(), m.period), ([1, 2, 3, 4, 5, 6], 16)),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            remote.wait(),
+            Ok(Event::Button(ButtonEvent {
+                press: true,
+                bnum: 7
+            }))
+        ));
+        assert_eq!(remote.wait().unwrap_err(), SpnavError::Wait);
+    }
+
+    #[test]
+    fn poll_buffers_partial_frames() {
+        let (mut a, b) = UnixStream::pair().unwrap();
+        b.set_nonblocking(true).unwrap();
+        let mut remote = RemoteSource::new(b);
+        assert!(remote.poll().is_none());
+        let mut frame = Vec::new();
+        let ev = Event::Button(ButtonEvent {
+            press: false,
+            bnum: 1,
+        });
+        write_frame(&mut frame, &ev).unwrap();
+        a.write_all(&frame[..6]).unwrap();
+        assert!(remote.poll().is_none());
+        a.write_all(&frame[6..]).unwrap();
+        assert!(matches!(remote.poll(), Some(Event::Button(_))));
+    }
+
+    #[test]
+    fn skips_undecodable_frame() {
+        let (mut a, b) = UnixStream::pair().unwrap();
+        let garbage = br#"{"type":"hat","dir":3}"#;
+        a.write_all(&(garbage.len() as u32).to_be_bytes()).unwrap();
+        a.write_all(garbage).unwrap();
+        let ev = Event::Button(ButtonEvent {
+            press: true,
+            bnum: 2,
+        });
+        write_frame(&mut a, &ev).unwrap();
+        let mut remote = RemoteSource::new(b);
+        assert_eq!(remote.wait().unwrap_err(), SpnavError::Wait);
+        assert!(matches!(
+            remote.wait(),
+            Ok(Event::Button(ButtonEvent {
+                press: true,
+                bnum: 2
+            }))
+        ));
+    }
+}
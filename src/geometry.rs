@@ -0,0 +1,30 @@
+use crate::MotionEvent;
+use euclid::Vector3D;
+
+impl MotionEvent {
+    /// Translation axes `(x, y, z)` as a vector tagged with the unit `U`.
+    pub fn translation<U>(&self) -> Vector3D<f64, U> {
+        Vector3D::new(self.x as f64, self.y as f64, self.z as f64)
+    }
+
+    /// Rotation axes `(rx, ry, rz)` as a vector tagged with the unit `U`.
+    pub fn rotation<U>(&self) -> Vector3D<f64, U> {
+        Vector3D::new(self.rx as f64, self.ry as f64, self.rz as f64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct DeviceSpace;
+
+    #[test]
+    fn typed_vectors() {
+        let m = MotionEvent::new([1, 2, 3, 4, 5, 6], 8);
+        let t: Vector3D<f64, DeviceSpace> = m.translation();
+        let r = m.rotation::<DeviceSpace>();
+        assert_eq!(t.to_tuple(), (1.0, 2.0, 3.0));
+        assert_eq!(r.to_tuple(), (4.0, 5.0, 6.0));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::Event;
+use std::collections::VecDeque;
+
+/// Ring buffer of the most recent events.
+///
+/// Holds at most `capacity` events; pushing onto a full history discards
+/// the oldest event. Memory use is `capacity * size_of::<Event>()` plus
+/// the [extra axes](crate::MotionEvent::extra) of the motion events held,
+/// which are empty on standard devices.
+#[derive(Debug, Clone)]
+pub struct EventHistory {
+    events: VecDeque<Event>,
+    capacity: usize,
+}
+
+impl EventHistory {
+    pub fn new(capacity: usize) -> Self {
+        EventHistory {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, ev: Event) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(ev);
+    }
+
+    /// Iterates from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter()
+    }
+
+    pub fn last(&self) -> Option<&Event> {
+        self.events.back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Passes `events` through unchanged while recording each one, e.g.
+    /// `history.tap(conn.poll_iter())`.
+    pub fn tap<'a, I>(&'a mut self, events: I) -> impl Iterator<Item = Event> + 'a
+    where
+        I: IntoIterator<Item = Event>,
+        I::IntoIter: 'a,
+    {
+        events.into_iter().inspect(|ev| self.push(ev.clone()))
+    }
+}
+
+impl Extend<Event> for EventHistory {
+    fn extend<I: IntoIterator<Item = Event>>(&mut self, events: I) {
+        for ev in events {
+            self.push(ev);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ButtonEvent;
+
+    fn button(bnum: i32) -> Event {
+        Event::Button(ButtonEvent { press: true, bnum })
+    }
+
+    fn bnums(h: &EventHistory) -> Vec<i32> {
+        h.iter()
+            .map(|ev| match ev {
+                Event::Button(b) => b.bnum,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn overflow_discards_oldest() {
+        let mut h = EventHistory::new(3);
+        h.extend((0..5).map(button));
+        assert_eq!(h.len(), 3);
+        assert_eq!(bnums(&h), [2, 3, 4]);
+        assert!(matches!(
+            h.last(),
+            Some(Event::Button(ButtonEvent { bnum: 4, .. }))
+        ));
+    }
+
+    #[test]
+    fn tap_records_polled_events() {
+        let c = crate::Connection::new_mock((0..2).map(button));
+        let mut h = EventHistory::new(8);
+        assert_eq!(h.tap(c.poll_iter()).count(), 2);
+        assert_eq!(bnums(&h), [0, 1]);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{Connection, Protocol};
+
+/// Snapshot of what can be queried about a [`Connection`], for logs and
+/// diagnostics panels. Fields the daemon or linked libspnav can't report
+/// are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionInfo {
+    pub fd: i32,
+    pub protocol: Protocol,
+    pub device_name: Option<String>,
+    pub num_buttons: Option<usize>,
+    pub num_axes: Option<usize>,
+    pub sensitivity: Option<f64>,
+    pub protocol_version: Option<i32>,
+}
+
+impl Connection {
+    /// Queries everything in [`ConnectionInfo`] at once. A failed query
+    /// leaves its field `None` rather than failing the snapshot.
+    pub fn info(&self) -> ConnectionInfo {
+        ConnectionInfo {
+            fd: self.live_fd(),
+            protocol: self.protocol(),
+            device_name: self.device_name().ok(),
+            num_buttons: self.num_buttons().ok(),
+            num_axes: self.num_axes().ok(),
+            sensitivity: self.sensitivity().ok(),
+            protocol_version: self.backend.protocol_version().ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{mock, Backend, ConnectionBuilder, Protocol};
+
+    #[test]
+    fn info_from_mock() {
+        let mock = mock::Mock::new(None)
+            .with_device_name("3Dconnexion SpaceNavigator")
+            .with_device(2, 6, 1);
+        let c = ConnectionBuilder::new()
+            .sensitivity_percent(50.0)
+            .open_backend(Backend::Mock(mock.into()));
+        let info = c.info();
+        assert_eq!(info.protocol, Protocol::Mock);
+        assert_eq!(
+            info.device_name.as_deref(),
+            Some("3Dconnexion SpaceNavigator")
+        );
+        assert_eq!((info.num_buttons, info.num_axes), (Some(2), Some(6)));
+        assert_eq!(info.sensitivity, Some(1.0));
+        assert_eq!(info.protocol_version, Some(1));
+
+        let bare = crate::Connection::new_mock(None).info();
+        assert_eq!(bare.device_name, None);
+        assert_eq!(bare.sensitivity, None);
+    }
+}
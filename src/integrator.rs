@@ -0,0 +1,194 @@
+use crate::MotionEvent;
+use std::time::Duration;
+
+/// How an [`Integrator`] interprets incoming motion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MotionMode {
+    /// The pose is the most recent event, scaled by the gains.
+    #[default]
+    Relative,
+    /// Events are treated as rates, the cap's deflection being its
+    /// velocity, and integrated over their `period` into a running pose.
+    Absolute,
+}
+
+/// Default cap, in milliseconds, on the `period` of the first event fed to
+/// an [`Integrator`]: roughly one frame at 60Hz.
+pub const DEFAULT_FIRST_PERIOD_MAX: u32 = 16;
+
+/// Turns motion events into a 6-DOF pose `[x, y, z, rx, ry, rz]`.
+///
+/// In [`MotionMode::Absolute`] each axis accumulates
+/// `value * gain * period_seconds`, and the result is clamped to the
+/// configured limits. This rate model is shared by
+/// [`DeltaTracker`](crate::DeltaTracker), which reports the change in
+/// position, and [`VelocityEstimator`](crate::VelocityEstimator), which
+/// smooths the rate itself.
+///
+/// The first event after connecting (or after [`Integrator::reset`]) often
+/// reports a `period` covering the whole idle time before it, which would
+/// make the pose jump. Its period is therefore capped at
+/// [`DEFAULT_FIRST_PERIOD_MAX`] unless configured otherwise with
+/// [`Integrator::with_first_period_max`].
+#[derive(Debug, Clone)]
+pub struct Integrator {
+    mode: MotionMode,
+    gains: [f64; 6],
+    min: [f64; 6],
+    max: [f64; 6],
+    first_period_max: Option<u32>,
+    first: bool,
+    pose: [f64; 6],
+}
+
+impl Integrator {
+    pub fn new(mode: MotionMode) -> Self {
+        Integrator {
+            mode,
+            gains: [1.0; 6],
+            min: [f64::NEG_INFINITY; 6],
+            max: [f64::INFINITY; 6],
+            first_period_max: Some(DEFAULT_FIRST_PERIOD_MAX),
+            first: true,
+            pose: [0.0; 6],
+        }
+    }
+
+    pub fn with_gains(mut self, gains: [f64; 6]) -> Self {
+        self.gains = gains;
+        self
+    }
+
+    /// Clamps each axis of the pose to `min[i]..=max[i]` in
+    /// [`MotionMode::Absolute`]. Infinite limits leave an axis unbounded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a limit is NaN or `min[i] > max[i]` for some axis.
+    pub fn with_limits(mut self, min: [f64; 6], max: [f64; 6]) -> Self {
+        for (i, (lo, hi)) in min.iter().zip(&max).enumerate() {
+            assert!(lo <= hi, "invalid limits {}..={} for axis {}", lo, hi, i);
+        }
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Sets the cap on the first event's period in milliseconds, or
+    /// disables it with `None`.
+    pub fn with_first_period_max(mut self, max: Option<u32>) -> Self {
+        self.first_period_max = max;
+        self
+    }
+
+    pub fn mode(&self) -> MotionMode {
+        self.mode
+    }
+
+    pub fn feed(&mut self, ev: &MotionEvent) {
+        let axes = ev.axes();
+        let period = match self.first_period_max {
+            Some(max) if self.first => ev.period.min(max),
+            _ => ev.period,
+        };
+        self.first = false;
+        let dt = period as f64 / 1000.0;
+        for (i, &a) in axes.iter().enumerate() {
+            let v = a as f64 * self.gains[i];
+            let p = match self.mode {
+                MotionMode::Relative => v,
+                MotionMode::Absolute => self.pose[i] + v * dt,
+            };
+            self.pose[i] = p.clamp(self.min[i], self.max[i]);
+        }
+    }
+
+    pub fn pose(&self) -> [f64; 6] {
+        self.pose
+    }
+
+    pub fn reset(&mut self) {
+        self.pose = [0.0; 6];
+        self.first = true;
+    }
+}
+
+impl MotionEvent {
+    /// Scales each axis by `target_dt / period`, giving the share of this
+    /// event's motion that falls in one frame of length `target_dt`.
+    ///
+    /// An event spanning 32 ms contributes half its values to a 16 ms
+    /// frame, and one spanning 8 ms twice its values, so motion per frame
+    /// stays consistent whatever the device's report rate. Unlike a
+    /// velocity, which would normalize to units per second, the result is
+    /// still in counts per frame. An event with a zero period is taken to
+    /// span exactly one frame.
+    pub fn per_frame(&self, target_dt: Duration) -> [f64; 6] {
+        let scale = if self.period == 0 {
+            1.0
+        } else {
+            target_dt.as_secs_f64() / Duration::from_millis(self.period.into()).as_secs_f64()
+        };
+        self.axes().map(|v| v as f64 * scale)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn motion(x: i32, period: u32) -> MotionEvent {
+        MotionEvent::new([x, 0, 0, 0, 0, 0], period)
+    }
+
+    #[test]
+    fn per_frame_scales_by_period() {
+        let frame = Duration::from_millis(16);
+        assert_eq!(motion(100, 32).per_frame(frame)[0], 50.0);
+        assert_eq!(motion(100, 8).per_frame(frame)[0], 200.0);
+        assert_eq!(motion(100, 0).per_frame(frame)[0], 100.0);
+    }
+
+    #[test]
+    fn absolute_accumulates_and_clamps() {
+        let mut i = Integrator::new(MotionMode::Absolute)
+            .with_gains([2.0; 6])
+            .with_limits([-10.0; 6], [10.0; 6]);
+        i.feed(&motion(1000, 2));
+        i.feed(&motion(1000, 2));
+        assert_eq!(i.pose()[0], 8.0);
+        i.feed(&motion(1000, 2));
+        assert_eq!(i.pose()[0], 10.0);
+        i.reset();
+        assert_eq!(i.pose(), [0.0; 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid limits NaN..=1 for axis 2")]
+    fn nan_limit_panics() {
+        let mut min = [-1.0; 6];
+        min[2] = f64::NAN;
+        Integrator::new(MotionMode::Absolute).with_limits(min, [1.0; 6]);
+    }
+
+    #[test]
+    fn first_period_is_clamped() {
+        let mut i = Integrator::new(MotionMode::Absolute);
+        i.feed(&motion(1000, 60_000));
+        assert_eq!(i.pose()[0], 16.0);
+        i.feed(&motion(1000, 100));
+        assert_eq!(i.pose()[0], 116.0);
+
+        let mut i = Integrator::new(MotionMode::Absolute).with_first_period_max(None);
+        i.feed(&motion(1000, 60_000));
+        assert_eq!(i.pose()[0], 60_000.0);
+    }
+
+    #[test]
+    fn relative_tracks_last_event() {
+        let mut i = Integrator::new(MotionMode::Relative);
+        i.feed(&motion(5, 16));
+        i.feed(&motion(3, 16));
+        assert_eq!(i.pose()[0], 3.0);
+    }
+}
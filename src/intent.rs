@@ -0,0 +1,230 @@
+use crate::{FullScale, MotionEvent};
+
+/// Per-intent multipliers for [`MotionEvent::to_cad_intent_with`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CadGains {
+    pub zoom: f64,
+    pub pan: f64,
+    pub orbit: f64,
+    pub full_scale: FullScale,
+}
+
+impl Default for CadGains {
+    fn default() -> Self {
+        CadGains {
+            zoom: 1.0,
+            pan: 1.0,
+            orbit: 1.0,
+            full_scale: FullScale::default(),
+        }
+    }
+}
+
+/// Motion interpreted the way CAD viewers usually do.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CadIntent {
+    pub zoom: f64,
+    pub pan: (f64, f64),
+    pub orbit: (f64, f64, f64),
+}
+
+/// Gains for [`MotionEvent::to_orbit_delta`].
+///
+/// `max_elevation` bounds the magnitude of each event's elevation change
+/// so a hard tilt can't flip the camera over the pole in one step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitGains {
+    pub azimuth: f64,
+    pub elevation: f64,
+    pub zoom: f64,
+    pub max_elevation: f64,
+    pub full_scale: FullScale,
+}
+
+impl Default for OrbitGains {
+    fn default() -> Self {
+        OrbitGains {
+            azimuth: 1.0,
+            elevation: 1.0,
+            zoom: 1.0,
+            max_elevation: f64::INFINITY,
+            full_scale: FullScale::default(),
+        }
+    }
+}
+
+/// Change to apply to an orbit camera.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct OrbitDelta {
+    pub azimuth: f64,
+    pub elevation: f64,
+    pub zoom: f64,
+}
+
+/// Dominant gesture of a motion event, see [`MotionEvent::classify_gesture`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    Pan,
+    Zoom,
+    /// Tilting the cap forward or back: `rx`.
+    Tilt,
+    /// Twisting the cap about the vertical axis: `ry`.
+    Spin,
+    /// Rotating the cap about the viewing axis, left or right: `rz`.
+    Roll,
+    Mixed,
+    None,
+}
+
+impl MotionEvent {
+    /// Classifies the event by which axis group dominates.
+    ///
+    /// The groups are pan `(x, y)`, zoom `z`, tilt `rx`, spin `ry` and
+    /// roll `rz`, matching the axis names used by
+    /// [`Event::describe`](crate::Event::describe); a group's magnitude
+    /// is that of its largest axis. If no group moves by more than
+    /// `thresh` counts the result is [`Gesture::None`]. Otherwise the
+    /// largest group wins, ties going to the earlier group above, unless
+    /// the runner-up is also over `thresh` and more than half the largest,
+    /// which gives [`Gesture::Mixed`].
+    pub fn classify_gesture(&self, thresh: i32) -> Gesture {
+        /// How many times the runner-up the largest group must be to win.
+        const MIXED_RATIO: u64 = 2;
+        let [x, y, z, rx, ry, rz] = self.axes().map(i32::unsigned_abs);
+        let thresh = thresh.max(0).unsigned_abs();
+        let mut groups = [
+            (x.max(y), Gesture::Pan),
+            (z, Gesture::Zoom),
+            (rx, Gesture::Tilt),
+            (ry, Gesture::Spin),
+            (rz, Gesture::Roll),
+        ];
+        groups.sort_by_key(|(m, _)| std::cmp::Reverse(*m));
+        let [(top, g), (second, _), ..] = groups;
+        if top <= thresh {
+            Gesture::None
+        } else if second > thresh && u64::from(second) * MIXED_RATIO > u64::from(top) {
+            Gesture::Mixed
+        } else {
+            g
+        }
+    }
+
+    /// Maps motion onto orbit camera controls.
+    ///
+    /// Twisting about the vertical axis (`ry`) turns the azimuth, tilting
+    /// forward/back (`rx`) changes the elevation and push/pull (`z`) zooms.
+    /// Values are normalized by `gains.full_scale` before the gains are
+    /// applied; other axes are ignored.
+    pub fn to_orbit_delta(&self, gains: OrbitGains) -> OrbitDelta {
+        let [_, _, z, rx, ry, _] = self.axes().map(|v| gains.full_scale.normalize(v));
+        let max = gains.max_elevation.abs();
+        OrbitDelta {
+            azimuth: ry * gains.azimuth,
+            elevation: (rx * gains.elevation).clamp(-max, max),
+            zoom: z * gains.zoom,
+        }
+    }
+
+    /// The rotation axes as a rotation vector, split into a unit axis and
+    /// an angle in radians.
+    ///
+    /// `(rx, ry, rz)` point along the rotation axis with a length
+    /// proportional to the angle; `scale` converts counts to radians.
+    /// A (near) zero rotation returns the x axis with angle 0.
+    pub fn rotation_axis_angle(&self, scale: f64) -> ([f64; 3], f64) {
+        let (rx, ry, rz) = self.r();
+        let v = [rx as f64, ry as f64, rz as f64];
+        let len = v.iter().map(|c| c * c).sum::<f64>().sqrt();
+        let angle = len * scale;
+        if len == 0.0 || angle.abs() < 1e-12 {
+            return ([1.0, 0.0, 0.0], 0.0);
+        }
+        (v.map(|c| c / len), angle)
+    }
+
+    /// [`MotionEvent::to_cad_intent_with`] using unit gains.
+    pub fn to_cad_intent(&self) -> CadIntent {
+        self.to_cad_intent_with(CadGains::default())
+    }
+
+    /// Splits motion into CAD viewer intents.
+    ///
+    /// Push/pull (`z`) zooms, the lateral axes `(x, y)` pan and tilt/twist
+    /// `(rx, ry, rz)` orbit. Values are normalized by `gains.full_scale`
+    /// and then multiplied by the matching gain.
+    pub fn to_cad_intent_with(&self, gains: CadGains) -> CadIntent {
+        let [x, y, z, rx, ry, rz] = self.axes().map(|v| gains.full_scale.normalize(v));
+        CadIntent {
+            zoom: z * gains.zoom,
+            pan: (x * gains.pan, y * gains.pan),
+            orbit: (rx * gains.orbit, ry * gains.orbit, rz * gains.orbit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn orbit_delta_clamps_elevation() {
+        let m = MotionEvent::new([0, 0, 0, 350, -175, 0], 8);
+        let d = m.to_orbit_delta(OrbitGains {
+            azimuth: 2.0,
+            max_elevation: 0.25,
+            ..Default::default()
+        });
+        assert_eq!(
+            d,
+            OrbitDelta {
+                azimuth: -1.0,
+                elevation: 0.25,
+                zoom: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn classifies_gestures() {
+        let mut m = MotionEvent::new([3, 0, -120, 0, 0, -5], 8);
+        assert_eq!(m.classify_gesture(10), Gesture::Zoom);
+        m.ry = 80;
+        assert_eq!(m.classify_gesture(10), Gesture::Mixed);
+        assert_eq!(m.classify_gesture(200), Gesture::None);
+    }
+
+    #[test]
+    fn dominant_group_beats_one_just_over_threshold() {
+        let m = MotionEvent::new([0, 12, 0, 0, 0, 150], 8);
+        assert_eq!(m.classify_gesture(10), Gesture::Roll);
+        let m = MotionEvent::new([0, 0, 0, 0, 11, 0], 8);
+        assert_eq!(m.classify_gesture(10), Gesture::Spin);
+    }
+
+    #[test]
+    fn axis_angle_of_twist() {
+        let mut m = MotionEvent::new([30, 0, 0, 0, -200, 0], 8);
+        assert_eq!(m.rotation_axis_angle(0.001), ([0.0, -1.0, 0.0], 0.2));
+        m.ry = 200;
+        assert_eq!(m.rotation_axis_angle(0.001), ([0.0, 1.0, 0.0], 0.2));
+        m.ry = 0;
+        assert_eq!(m.rotation_axis_angle(0.001), ([1.0, 0.0, 0.0], 0.0));
+    }
+
+    #[test]
+    fn pure_push_only_zooms() {
+        let m = MotionEvent::new([0, 0, 175, 0, 0, 0], 8);
+        let intent = m.to_cad_intent_with(CadGains {
+            zoom: 2.0,
+            ..Default::default()
+        });
+        assert_eq!(
+            intent,
+            CadIntent {
+                zoom: 1.0,
+                ..Default::default()
+            }
+        );
+    }
+}
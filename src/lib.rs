@@ -1,9 +1,117 @@
 use libspnav_bindings as libspnav;
-use std::convert::{From, Into, TryFrom};
+use std::convert::{From, TryFrom};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Mutex;
 use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Copy)]
+mod bindings;
+mod builder;
+mod buttons;
+pub mod csv;
+mod delta;
+mod describe;
+mod device;
+mod dispatch;
+mod drift;
+mod feel;
+mod filter;
+#[cfg(feature = "serde")]
+mod forward;
+#[cfg(feature = "euclid")]
+mod geometry;
+mod history;
+mod info;
+mod integrator;
+mod intent;
+#[cfg(feature = "message")]
+mod message;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+mod reader;
+#[cfg(feature = "serde")]
+mod recording;
+mod scroll;
+mod sensitivity;
+mod source;
+#[cfg(feature = "tokio")]
+mod stream;
+#[cfg(feature = "tracing")]
+mod trace;
+mod transform;
+mod velocity;
+mod watchdog;
+
+pub use bindings::{BindingRecorder, Bindings};
+pub use builder::ConnectionBuilder;
+pub use buttons::{ButtonRepeater, ButtonState, KeyLike};
+pub use delta::{AxisDeltas, DeltaTracker};
+pub use device::{button_from_id, classify_model, list_devices, DeviceInfo, DeviceModel};
+pub use dispatch::dispatch;
+pub use drift::DriftMonitor;
+pub use feel::FeelProfile;
+pub use filter::OneEuroFilter;
+#[cfg(feature = "serde")]
+pub use forward::{write_frame, Forwarder, RemoteSource};
+pub use history::EventHistory;
+pub use info::ConnectionInfo;
+pub use integrator::{Integrator, MotionMode, DEFAULT_FIRST_PERIOD_MAX};
+pub use intent::{CadGains, CadIntent, Gesture, OrbitDelta, OrbitGains};
+#[cfg(feature = "message")]
+pub use message::EventMessage;
+#[cfg(feature = "serde")]
+pub use recording::{load_recording, replay_to_vec, save_recording, TimedEvent};
+pub use scroll::{ScrollAccumulator, ScrollTick};
+pub use sensitivity::{sensitivity_from_percent, sensitivity_to_percent};
+pub use source::EventSource;
+#[cfg(feature = "tokio")]
+pub use stream::{ButtonStream, MotionStream, STREAM_CAPACITY};
+pub use transform::{
+    AxisCurve, AxisMap, Deadzone, FullScale, MotionTransform, Pipeline, ResponseCurve,
+    DEFAULT_FULL_SCALE,
+};
+pub use velocity::VelocityEstimator;
+pub use watchdog::Watchdog;
+
+/// Errors reported by this crate. New variants may be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SpnavError {
+    Open,
+    Close,
+    Fd,
+    Sensitivity,
+    Wait,
+    UnknownEventType(i32),
+    Unsupported,
+    Query,
+    /// The daemon link went away mid-session, e.g. because spacenavd
+    /// exited. Recover with [`Connection::reconnect`].
+    ConnectionLost,
+}
+
+impl fmt::Display for SpnavError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpnavError::Open => write!(f, "failed to open spacenav connection"),
+            SpnavError::Close => write!(f, "failed to close spacenav connection"),
+            SpnavError::Fd => write!(f, "no spacenav connection file descriptor"),
+            SpnavError::Sensitivity => write!(f, "failed to set spacenav sensitivity"),
+            SpnavError::Wait => write!(f, "failed to wait for spacenav event"),
+            SpnavError::UnknownEventType(t) => write!(f, "unknown spacenav event type {}", t),
+            SpnavError::Unsupported => write!(f, "not supported by the linked libspnav"),
+            SpnavError::Query => write!(f, "spacenav daemon request failed"),
+            SpnavError::ConnectionLost => write!(f, "lost connection to the spacenav daemon"),
+        }
+    }
+}
+
+impl std::error::Error for SpnavError {}
+
+/// libspnav event categories. New variants may be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum EventType {
     Any,
     Motion,
@@ -14,9 +122,18 @@ const SPNAV_EVENT_ANY: i32 = 0;
 const SPNAV_EVENT_MOTION: i32 = 1;
 const SPNAV_EVENT_BUTTON: i32 = 2;
 
-impl Into<i32> for EventType {
-    fn into(self) -> i32 {
-        match self {
+/// Size in bytes of libspnav's `spnav_event` union in the linked bindings.
+pub const EVENT_SIZE: usize = std::mem::size_of::<libspnav::spnav_event>();
+
+impl EventType {
+    pub(crate) fn matches(self, event: &Event) -> bool {
+        self == EventType::Any || self == event.event_type()
+    }
+}
+
+impl From<EventType> for i32 {
+    fn from(t: EventType) -> i32 {
+        match t {
             EventType::Any => SPNAV_EVENT_ANY,
             EventType::Motion => SPNAV_EVENT_MOTION,
             EventType::Button => SPNAV_EVENT_BUTTON,
@@ -24,13 +141,70 @@ impl Into<i32> for EventType {
     }
 }
 
+/// An event read from the device. New variants may be added, so
+/// downstream matches need a wildcard arm.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "lowercase"))]
+#[non_exhaustive]
 pub enum Event {
     Motion(MotionEvent),
     Button(ButtonEvent),
 }
 
+/// Fixed-size, allocation-free encoding of an [`Event`].
+///
+/// `kind` holds the libspnav event type. Motion events store
+/// `[x, y, z, rx, ry, rz, period]` in `data`, dropping any
+/// [extra axes](MotionEvent::extra); button events store `[press, bnum]`
+/// followed by zeroes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EventRaw {
+    pub kind: i32,
+    pub data: [i32; 7],
+}
+
+impl Event {
+    pub fn event_type(&self) -> EventType {
+        match self {
+            Event::Motion(_) => EventType::Motion,
+            Event::Button(_) => EventType::Button,
+        }
+    }
+
+    pub fn encode(&self) -> EventRaw {
+        match self {
+            Event::Motion(m) => EventRaw {
+                kind: SPNAV_EVENT_MOTION,
+                data: [m.x, m.y, m.z, m.rx, m.ry, m.rz, m.period as i32],
+            },
+            Event::Button(b) => EventRaw {
+                kind: SPNAV_EVENT_BUTTON,
+                data: [b.press as i32, b.bnum, 0, 0, 0, 0, 0],
+            },
+        }
+    }
+
+    pub fn decode(raw: EventRaw) -> Result<Event, SpnavError> {
+        let d = raw.data;
+        match raw.kind {
+            SPNAV_EVENT_MOTION => Ok(Event::Motion(MotionEvent::new(
+                [d[0], d[1], d[2], d[3], d[4], d[5]],
+                d[6] as u32,
+            ))),
+            SPNAV_EVENT_BUTTON => Ok(Event::Button(ButtonEvent {
+                press: d[0] != 0,
+                bnum: d[1],
+            })),
+            t => Err(SpnavError::UnknownEventType(t)),
+        }
+    }
+}
+
+/// Motion of the cap. Build one with [`MotionEvent::new`].
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MotionEvent {
     pub x: i32,
     pub y: i32,
@@ -39,9 +213,43 @@ pub struct MotionEvent {
     pub ry: i32,
     pub rz: i32,
     pub period: u32,
+    /// Axes beyond the first six, e.g. a thumb puck on high-end devices.
+    /// Empty on standard six-axis devices.
+    ///
+    /// libspnav has no documented way to hand over more than six axis
+    /// values, so events read from it always leave this empty; it is
+    /// filled by other sources, such as a forwarded or recorded stream.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub extra: Vec<i32>,
 }
 
+/// Index of each axis in [`MotionEvent::axes`].
+pub const TX: usize = 0;
+pub const TY: usize = 1;
+pub const TZ: usize = 2;
+pub const RX: usize = 3;
+pub const RY: usize = 4;
+pub const RZ: usize = 5;
+
 impl MotionEvent {
+    /// An event with the given [`axes`](Self::axes) and no extra axes.
+    pub fn new(axes: [i32; 6], period: u32) -> Self {
+        let [x, y, z, rx, ry, rz] = axes;
+        MotionEvent {
+            x,
+            y,
+            z,
+            rx,
+            ry,
+            rz,
+            period,
+            extra: Vec::new(),
+        }
+    }
+
     pub fn t(&self) -> (i32, i32, i32) {
         (self.x, self.y, self.z)
     }
@@ -49,23 +257,74 @@ impl MotionEvent {
     pub fn r(&self) -> (i32, i32, i32) {
         (self.rx, self.ry, self.rz)
     }
+
+    /// All six axes, ordered `[x, y, z, rx, ry, rz]` as given by
+    /// [`TX`]..=[`RZ`].
+    pub fn axes(&self) -> [i32; 6] {
+        [self.x, self.y, self.z, self.rx, self.ry, self.rz]
+    }
+
+    /// Sets the axis at index `i` of [`MotionEvent::axes`].
+    ///
+    /// Panics if `i` is not in `0..6`.
+    pub fn set_axis(&mut self, i: usize, v: i32) {
+        match i {
+            TX => self.x = v,
+            TY => self.y = v,
+            TZ => self.z = v,
+            RX => self.rx = v,
+            RY => self.ry = v,
+            RZ => self.rz = v,
+            _ => panic!("axis index {} out of range", i),
+        }
+    }
+
+    /// Combines two consecutive events into one spanning both: each axis
+    /// and the period are summed, saturating at the integer bounds. Extra
+    /// axes missing from one event count as zero.
+    pub fn merge(&self, other: &MotionEvent) -> MotionEvent {
+        let (long, short) = if self.extra.len() >= other.extra.len() {
+            (&self.extra, &other.extra)
+        } else {
+            (&other.extra, &self.extra)
+        };
+        let mut extra = long.clone();
+        for (e, v) in extra.iter_mut().zip(short) {
+            *e = e.saturating_add(*v);
+        }
+        MotionEvent {
+            x: self.x.saturating_add(other.x),
+            y: self.y.saturating_add(other.y),
+            z: self.z.saturating_add(other.z),
+            rx: self.rx.saturating_add(other.rx),
+            ry: self.ry.saturating_add(other.ry),
+            rz: self.rz.saturating_add(other.rz),
+            period: self.period.saturating_add(other.period),
+            extra,
+        }
+    }
+
+    /// Multiplies each axis by its gain, rounding and saturating.
+    pub(crate) fn scale_axes(&mut self, gains: [f64; 6]) {
+        for (i, v) in self.axes().into_iter().enumerate() {
+            self.set_axis(i, (v as f64 * gains[i]).round() as i32);
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.t() == (0, 0, 0) && self.r() == (0, 0, 0) && self.extra.iter().all(|&v| v == 0)
+    }
 }
 
 impl From<libspnav::spnav_event_motion> for MotionEvent {
     fn from(event: libspnav::spnav_event_motion) -> Self {
-        MotionEvent {
-            x: event.x,
-            y: event.y,
-            z: event.z,
-            rx: event.rx,
-            ry: event.ry,
-            rz: event.rz,
-            period: event.period,
-        }
+        let axes = [event.x, event.y, event.z, event.rx, event.ry, event.rz];
+        MotionEvent::new(axes, event.period)
     }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ButtonEvent {
     pub press: bool,
     pub bnum: i32,
@@ -81,7 +340,7 @@ impl From<libspnav::spnav_event_button> for ButtonEvent {
 }
 
 impl TryFrom<libspnav::spnav_event> for Event {
-    type Error = ();
+    type Error = SpnavError;
     fn try_from(event: libspnav::spnav_event) -> Result<Self, Self::Error> {
         unsafe {
             match event {
@@ -91,56 +350,779 @@ impl TryFrom<libspnav::spnav_event> for Event {
                 libspnav::spnav_event {
                     type_: SPNAV_EVENT_BUTTON,
                 } => Ok(Event::Button(event.button.into())),
-                _ => Err(()),
+                libspnav::spnav_event { type_ } => Err(SpnavError::UnknownEventType(type_)),
             }
         }
     }
 }
 
+/// A handle on the process-wide libspnav connection.
+///
+/// Each `Connection` has a lookahead buffer used by
+/// [`Connection::peek_type`] and [`Connection::any_motion_pending`].
+/// Events held there are returned, in order, before any new event is read
+/// from libspnav, by `poll`, `wait` and `drain` alike.
+///
+/// With the `tracing` feature, each call to `poll` and `wait` runs in a
+/// trace-level span of the same name. When an event is returned, the span
+/// records its `kind` ("motion" or "button") and, for motion, its
+/// `period`.
 #[derive(Debug)]
 pub struct Connection {
+    /// The link's descriptor as of opening, or this connection's last
+    /// [`reconnect`](Connection::reconnect). A reconnect through another
+    /// connection sharing the link leaves it stale; the methods here always
+    /// use the link's current descriptor.
     pub fd: i32,
+    backend: Backend,
+    options: builder::Options,
+    lookahead: Mutex<std::collections::VecDeque<Event>>,
+    #[cfg(feature = "test-inject")]
+    synthetic: Mutex<std::collections::VecDeque<Event>>,
+    paused: AtomicBool,
+    /// Event mask to restore on [`Connection::resume`].
+    resume_mask: AtomicU32,
+    closed: bool,
+}
+
+/// Transport a [`Connection`] talks to. New variants may be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Protocol {
+    /// spacenavd's AF_UNIX socket, as opened by `spnav_open`. This crate
+    /// doesn't expose libspnav's X11 transport.
+    AfUnix,
+    /// The in-memory backend from the `mock` feature.
+    Mock,
+}
+
+#[derive(Debug, Clone)]
+enum Backend {
+    Spnav,
+    #[cfg(any(test, feature = "mock"))]
+    Mock(std::sync::Arc<mock::Mock>),
+}
+
+impl Backend {
+    /// The state of this backend's shared link.
+    fn link(&self) -> &Mutex<Link> {
+        match self {
+            Backend::Spnav => LINK.get_or_init(Mutex::default),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => &m.links,
+        }
+    }
+
+    fn open(&self) -> Result<(), SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_open(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => m.open(),
+        }
+    }
+
+    fn fd(&self) -> Result<i32, SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_fd(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(_) => Ok(-1),
+        }
+    }
+
+    fn close(&self) -> Result<(), SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_close(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => {
+                m.closes.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+        }
+    }
+
+    fn protocol(&self) -> Protocol {
+        match self {
+            Backend::Spnav => Protocol::AfUnix,
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(_) => Protocol::Mock,
+        }
+    }
+
+    fn poll(&self) -> Option<Event> {
+        match self {
+            Backend::Spnav => lib::spnav_poll_event(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => m.poll(),
+        }
+    }
+
+    fn wait(&self) -> Result<Event, SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_wait_event(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => m.wait(),
+        }
+    }
+
+    fn remove_events(&self, t: EventType) -> usize {
+        match self {
+            Backend::Spnav => lib::spnav_remove_events(t).max(0) as usize,
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => m.remove_events(t),
+        }
+    }
+
+    fn client_name(&self, name: &str) -> Result<(), SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_client_name(name),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => {
+                *m.client_name.lock().expect("to lock") = Some(name.to_owned());
+                Ok(())
+            }
+        }
+    }
+
+    fn sensitivity(&self, sens: f64) -> Result<(), SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_sensitivity(sens).map(|_| ()),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => {
+                *m.sensitivity.lock().expect("to lock") = Some(sens);
+                Ok(())
+            }
+        }
+    }
+
+    /// Waits up to `timeout` for `fd` to become readable.
+    fn wait_readable(&self, fd: i32, timeout: Duration) -> Result<bool, SpnavError> {
+        match self {
+            Backend::Spnav => lib::wait_readable(fd, timeout),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(_) => Ok(false),
+        }
+    }
+
+    /// Whether the link behind `fd` is gone: libspnav no longer reports
+    /// that descriptor, or the daemon hung up on it.
+    fn link_lost(&self) -> bool {
+        match self {
+            Backend::Spnav => lib::spnav_fd().map_or(true, lib::hung_up),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => m.lost.load(Ordering::Relaxed),
+        }
+    }
+
+    fn device_name(&self) -> Result<String, SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_dev_name(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => m.device_name.clone().ok_or(SpnavError::Unsupported),
+        }
+    }
+
+    /// Sets the event mask, returning the one it replaces.
+    fn evmask(&self, mask: u32) -> Result<u32, SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_evmask(mask),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => match m.evmask.lock().expect("to lock").as_mut() {
+                Some(current) => Ok(std::mem::replace(current, mask)),
+                None => Err(SpnavError::Unsupported),
+            },
+        }
+    }
+
+    fn protocol_version(&self) -> Result<i32, SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_protocol(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => m.protocol_version.ok_or(SpnavError::Unsupported),
+        }
+    }
+
+    fn num_buttons(&self) -> Result<usize, SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_dev_buttons(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => m.num_buttons.ok_or(SpnavError::Unsupported),
+        }
+    }
+
+    fn num_axes(&self) -> Result<usize, SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_dev_axes(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => m.num_axes.ok_or(SpnavError::Unsupported),
+        }
+    }
+
+    fn get_sensitivity(&self) -> Result<f64, SpnavError> {
+        match self {
+            Backend::Spnav => lib::spnav_cfg_get_sens(),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(m) => m
+                .sensitivity
+                .lock()
+                .expect("to lock")
+                .ok_or(SpnavError::Unsupported),
+        }
+    }
+}
+
+/// Version of the linked libspnav, as reported by pkg-config at build
+/// time. libspnav 0.x ships no pkg-config file, so this is usually `None`
+/// for it.
+pub fn library_version() -> Option<String> {
+    option_env!("SPNAV_LIBRARY_VERSION").map(String::from)
 }
 
-static CONN_COUNT: OnceLock<Mutex<usize>> = OnceLock::new();
+/// The state of a shared link: how many `Connection`s hold a share of it
+/// and whether it is actually open. A failed reconnect leaves it closed
+/// while connections still hold shares.
+#[derive(Debug, Default)]
+pub(crate) struct Link {
+    pub(crate) count: usize,
+    pub(crate) open: bool,
+}
+
+static LINK: OnceLock<Mutex<Link>> = OnceLock::new();
 
 impl Connection {
-    pub fn new() -> Result<Connection, ()> {
-        let conn_count = CONN_COUNT.get_or_init(|| Mutex::new(0));
-        let mut count = conn_count.lock().expect("to lock");
-        if *count > 0 {
-            *count += 1;
-            Ok(Connection {
-                fd: lib::spnav_fd()?,
-            })
-        } else {
-            *count = 1;
-            lib::spnav_open()?;
-            Ok(Connection {
-                fd: lib::spnav_fd()?,
-            })
+    pub fn new() -> Result<Connection, SpnavError> {
+        ConnectionBuilder::new().open()
+    }
+
+    pub fn builder() -> ConnectionBuilder {
+        ConnectionBuilder::new()
+    }
+
+    fn open_with(options: builder::Options) -> Result<Connection, SpnavError> {
+        Connection::open_on(Backend::Spnav, options)
+    }
+
+    /// Takes a share of `backend`'s link, opening it if no other
+    /// connection holds one. The count only changes once the connection
+    /// exists, so a failed open leaves it as it was and the next attempt
+    /// opens again.
+    fn open_on(backend: Backend, options: builder::Options) -> Result<Connection, SpnavError> {
+        let mut link = backend.link().lock().unwrap_or_else(|e| e.into_inner());
+        let first = !link.open;
+        if first {
+            backend.open()?;
+        }
+        let fd = match backend.fd() {
+            Ok(fd) => fd,
+            Err(e) => {
+                if first {
+                    let _ = backend.close();
+                }
+                return Err(e);
+            }
+        };
+        link.open = true;
+        link.count += 1;
+        drop(link);
+        Ok(Connection::with_backend(fd, backend, options))
+    }
+
+    fn with_backend(fd: i32, backend: Backend, options: builder::Options) -> Connection {
+        Connection {
+            fd,
+            backend,
+            options,
+            lookahead: Mutex::default(),
+            #[cfg(feature = "test-inject")]
+            synthetic: Mutex::default(),
+            paused: AtomicBool::new(false),
+            resume_mask: AtomicU32::new(lib::SPNAV_EVMASK_INPUT),
+            closed: false,
+        }
+    }
+
+    /// Creates a connection backed by an in-memory queue instead of
+    /// libspnav. `poll` hands out `events` in order and `wait` fails with
+    /// [`SpnavError::Wait`] once they run out rather than blocking.
+    ///
+    /// For tests and demos only; requires the `mock` feature.
+    #[cfg(any(test, feature = "mock"))]
+    pub fn new_mock(events: impl IntoIterator<Item = Event>) -> Connection {
+        ConnectionBuilder::new().open_mock(events)
+    }
+
+    /// Like [`Connection::new`], but falls back to an empty mock
+    /// connection that never yields events when libspnav can't be opened,
+    /// so demo apps run without hardware.
+    ///
+    /// For tests and demos only; requires the `mock` feature.
+    #[cfg(any(test, feature = "mock"))]
+    pub fn new_or_null() -> Connection {
+        Connection::new().unwrap_or_else(|_| Connection::new_mock(None))
+    }
+
+    /// Closes and reopens the libspnav link, then reapplies this
+    /// connection's builder settings.
+    ///
+    /// The link is shared by every `Connection` in the process, so they
+    /// all read from the new one, but only this connection's
+    /// [`fd`](Self::fd) field is updated. Events in its lookahead buffer
+    /// are discarded. If reopening fails the link stays closed until a later
+    /// `reconnect` succeeds.
+    pub fn reconnect(&mut self) -> Result<(), SpnavError> {
+        self.clear_lookahead();
+        {
+            let mut link = self
+                .backend
+                .link()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if link.open {
+                // The old link is presumed dead, so a failed close is
+                // expected.
+                let _ = self.backend.close();
+                link.open = false;
+            }
+            self.backend.open()?;
+            link.open = true;
+            self.fd = self.backend.fd()?;
+        }
+        ConnectionBuilder::configure(self)
+    }
+
+    /// The link's current descriptor. The `fd` field can be stale after a
+    /// reconnect through another connection, so libspnav is asked again;
+    /// mock connections have no link and keep using `fd`.
+    fn live_fd(&self) -> i32 {
+        match &self.backend {
+            Backend::Spnav => lib::spnav_fd().unwrap_or(-1),
+            #[cfg(any(test, feature = "mock"))]
+            Backend::Mock(_) => self.fd,
         }
     }
 
     pub fn poll(&self) -> Option<Event> {
-        lib::spnav_poll_event()
+        #[cfg(feature = "tracing")]
+        let span = trace::event_span!("poll").entered();
+        let event = self.take_lookahead().or_else(|| self.read_poll());
+        #[cfg(feature = "tracing")]
+        if let Some(event) = &event {
+            trace::record(&span, event);
+        }
+        event
+    }
+
+    /// Like [`poll`](Self::poll), but writes the event into `out`,
+    /// returning whether one was read. `out` is left untouched otherwise.
+    ///
+    /// Neither form allocates on standard six-axis devices, whose events
+    /// leave [`MotionEvent::extra`] empty, and `Option<Event>` is returned
+    /// on the stack, so this is no faster than `poll`: on a mock
+    /// connection, `cargo bench --features mock --bench poll` puts both at
+    /// about 60 ns per event, within run-to-run noise of each other. It
+    /// suits loops that keep one event slot around.
+    pub fn poll_into(&self, out: &mut Event) -> bool {
+        match self.poll() {
+            Some(event) => {
+                *out = event;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Like [`poll`](Self::poll), but an empty queue on a link that has
+    /// gone away is reported as [`SpnavError::ConnectionLost`].
+    pub fn try_poll(&self) -> Result<Option<Event>, SpnavError> {
+        match self.poll() {
+            Some(event) => Ok(Some(event)),
+            None if self.backend.link_lost() => Err(SpnavError::ConnectionLost),
+            None => Ok(None),
+        }
+    }
+
+    /// Blocks until the next event. Fails with
+    /// [`SpnavError::ConnectionLost`] if the daemon link has gone away.
+    pub fn wait(&self) -> Result<Event, SpnavError> {
+        #[cfg(feature = "tracing")]
+        let span = trace::event_span!("wait").entered();
+        let event = match self.take_lookahead() {
+            Some(event) => Ok(event),
+            None => self.read_wait(),
+        };
+        #[cfg(feature = "tracing")]
+        if let Ok(event) = &event {
+            trace::record(&span, event);
+        }
+        event
+    }
+
+    /// Queues `ev` to be read before anything from libspnav, for driving a
+    /// real connection from integration tests.
+    ///
+    /// Injected events go through the same processing as device events.
+    /// Testing only: without the `test-inject` feature this does nothing.
+    pub fn push_synthetic(&self, ev: Event) {
+        #[cfg(feature = "test-inject")]
+        self.synthetic.lock().expect("to lock").push_back(ev);
+        #[cfg(not(feature = "test-inject"))]
+        let _ = ev;
+    }
+
+    /// Like [`Connection::wait`], but gives up after `timeout`, returning
+    /// `Ok(None)`.
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<Option<Event>, SpnavError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(event) = self.try_poll()? {
+                return Ok(Some(event));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !self.backend.wait_readable(self.live_fd(), remaining)? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Waits up to `timeout` for an event, then also takes up to `max - 1`
+    /// more that are already queued, without blocking again. Returns an
+    /// empty batch on timeout or when `max` is zero.
+    pub fn wait_batch(&self, max: usize, timeout: Duration) -> Result<Vec<Event>, SpnavError> {
+        if max == 0 {
+            return Ok(Vec::new());
+        }
+        let Some(first) = self.wait_timeout(timeout)? else {
+            return Ok(Vec::new());
+        };
+        let mut batch = vec![first];
+        batch.extend(self.poll_iter().take(max - 1));
+        Ok(batch)
+    }
+
+    /// Like [`Connection::wait`], but also returns how long the call
+    /// blocked. An event already in the lookahead buffer returns at once.
+    pub fn wait_timed_block(&self) -> Result<(Event, Duration), SpnavError> {
+        let start = Instant::now();
+        let event = self.wait()?;
+        Ok((event, start.elapsed()))
+    }
+
+    /// Transport this connection was opened with.
+    pub fn protocol(&self) -> Protocol {
+        self.backend.protocol()
+    }
+
+    /// Sets the daemon's sensitivity multiplier. This affects every client
+    /// of the daemon, not just this connection.
+    pub fn set_sensitivity(&self, sens: f64) -> Result<(), SpnavError> {
+        self.backend.sensitivity(sens)
+    }
+
+    /// Sets the name spacenav configuration tools show for this client.
+    pub fn set_client_name(&self, name: &str) -> Result<(), SpnavError> {
+        self.backend.client_name(name)
+    }
+
+    /// Product name of the device the daemon is using.
+    pub fn device_name(&self) -> Result<String, SpnavError> {
+        self.backend.device_name()
+    }
+
+    /// Model of the device the daemon is using, from [`classify_model`].
+    pub fn model(&self) -> Result<DeviceModel, SpnavError> {
+        self.device_name().map(|name| classify_model(&name))
+    }
+
+    /// Number of buttons on the device the daemon is using.
+    pub fn num_buttons(&self) -> Result<usize, SpnavError> {
+        self.backend.num_buttons()
+    }
+
+    /// Number of axes on the device the daemon is using.
+    pub fn num_axes(&self) -> Result<usize, SpnavError> {
+        self.backend.num_axes()
+    }
+
+    /// The daemon's current sensitivity multiplier.
+    pub fn sensitivity(&self) -> Result<f64, SpnavError> {
+        self.backend.get_sensitivity()
+    }
+
+    /// Version reported by the spacenav daemon.
+    ///
+    /// spacenavd only reports its protocol version, which is returned as a
+    /// decimal string. Fails with [`SpnavError::Unsupported`] when the
+    /// linked libspnav can't query it.
+    pub fn daemon_version(&self) -> Result<String, SpnavError> {
+        self.backend.protocol_version().map(|v| v.to_string())
+    }
+
+    /// Stops delivering events until [`Connection::resume`].
+    ///
+    /// Where the daemon supports event masks it is asked to stop sending
+    /// events. Either way, events read while paused, including any held in
+    /// the lookahead buffer, are discarded rather than deferred, so a
+    /// `wait` while paused keeps blocking until an event arrives after
+    /// resuming.
+    ///
+    /// The event mask belongs to the process-wide libspnav link, so where
+    /// it is supported, pausing any connection stops delivery to every
+    /// connection sharing the link, clones included, and resuming any of
+    /// them restarts it. The discarding fallback only affects this
+    /// connection.
+    ///
+    /// Pausing a paused connection does nothing.
+    pub fn pause(&self) {
+        if self.paused.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // Unsupported masks fall back to discarding in `process`.
+        if let Ok(previous) = self.backend.evmask(0) {
+            self.resume_mask.store(previous, Ordering::SeqCst);
+        }
+        self.clear_lookahead();
+    }
+
+    /// Restarts delivery after [`Connection::pause`], first discarding
+    /// whatever queued up in the meantime, and restores the event mask
+    /// that was in force before pausing. Without event masks nothing
+    /// stops the daemon sending, so an app that stopped reading while
+    /// paused would otherwise get the whole stale backlog at once.
+    ///
+    /// Resuming a connection that isn't paused does nothing, so it is
+    /// safe to call on every focus-in.
+    pub fn resume(&self) {
+        if !self.paused.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        self.flush(EventType::Any);
+        let _ = self.backend.evmask(self.resume_mask.load(Ordering::SeqCst));
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Sets or clears `O_NONBLOCK` on the link's descriptor, for event loops
+    /// that read readiness off the descriptor themselves.
+    ///
+    /// `poll` never blocks either way, but a non-blocking descriptor makes
+    /// `wait` fail instead of sleeping when nothing is queued, so leave it
+    /// blocking for threads that call `wait`, such as the one behind
+    /// `split_streams` with the `tokio` feature. Fails with
+    /// [`SpnavError::Fd`] if the descriptor is invalid, as it is for mock
+    /// connections.
+    pub fn set_nonblocking(&self, nb: bool) -> Result<(), SpnavError> {
+        lib::set_nonblocking(self.live_fd(), nb)
+    }
+
+    /// Reports the type of the next event without consuming it.
+    ///
+    /// libspnav has no way to push an event back onto its queue, so the
+    /// polled event is parked in this connection's lookahead buffer and
+    /// handed out by the next `poll`, `wait` or `drain`. Returns `None` if
+    /// no event is queued.
+    pub fn peek_type(&self) -> Option<EventType> {
+        let mut buf = self.lookahead.lock().expect("to lock");
+        if buf.is_empty() {
+            buf.extend(self.read_poll());
+        }
+        buf.front().map(Event::event_type)
+    }
+
+    /// Whether any queued event is motion with a nonzero axis.
+    ///
+    /// Everything libspnav has queued is moved into the lookahead buffer
+    /// to be inspected, so no event is lost: the next `poll`, `wait` or
+    /// `drain` returns them in their original order.
+    pub fn any_motion_pending(&self) -> bool {
+        let mut buf = self.lookahead.lock().expect("to lock");
+        buf.extend(std::iter::from_fn(|| self.read_poll()));
+        buf.iter()
+            .any(|e| matches!(e, Event::Motion(m) if !m.is_zero()))
+    }
+
+    /// Iterates over queued events without blocking, ending when `poll`
+    /// returns `None`.
+    pub fn poll_iter(&self) -> impl Iterator<Item = Event> + '_ {
+        std::iter::from_fn(|| self.poll())
+    }
+
+    /// Returns every queued event without blocking, starting with the
+    /// lookahead buffer.
+    pub fn drain(&self) -> Vec<Event> {
+        self.poll_iter().collect()
+    }
+
+    /// Reads everything available, for an epoll handler watching
+    /// [`fd`](Self::fd).
+    ///
+    /// With edge-triggered epoll (`EPOLLET`) a wakeup is only reported when
+    /// new data arrives, so a handler that reads one event and returns can
+    /// leave the rest queued with no further wakeup and hang. This keeps
+    /// polling until `poll` returns `None`, which always empties what is
+    /// queued; with level-triggered epoll it just saves extra wakeups.
+    pub fn drain_on_ready(&self) -> Vec<Event> {
+        self.drain()
+    }
+
+    /// Discards every pending event of type `t`, returning how many were
+    /// removed.
+    ///
+    /// `spnav_remove_events` only clears what libspnav still holds; an
+    /// event this connection already read, e.g. into the lookahead buffer
+    /// by [`peek_type`](Self::peek_type), is out of its reach. So the
+    /// library queue is cleared first, then anything matching that was
+    /// already read is dropped too. libspnav also only removes events of
+    /// one specific type, so for [`EventType::Any`] the rest of the queue
+    /// is emptied by polling. Events queued with
+    /// [`push_synthetic`](Self::push_synthetic) are flushed as well.
+    pub fn flush(&self, t: EventType) -> usize {
+        let mut removed = self.backend.remove_events(t);
+        if t == EventType::Any {
+            while self.backend.poll().is_some() {
+                removed += 1;
+            }
+        }
+        #[cfg(feature = "test-inject")]
+        {
+            let mut synthetic = self.synthetic.lock().expect("to lock");
+            let before = synthetic.len();
+            synthetic.retain(|e| !t.matches(e));
+            removed += before - synthetic.len();
+        }
+        let mut buf = self.lookahead.lock().expect("to lock");
+        let before = buf.len();
+        buf.retain(|e| !t.matches(e));
+        removed + before - buf.len()
+    }
+
+    /// Closes the connection, reporting whether libspnav closed cleanly.
+    ///
+    /// Only the last open connection actually closes the libspnav link;
+    /// for the others this just gives up their share. Dropping a
+    /// `Connection` does the same but can only log a failure.
+    pub fn close(mut self) -> Result<(), SpnavError> {
+        self.release()
+    }
+
+    /// Gives up this connection's share of the libspnav link, closing it
+    /// if this was the last one. Runs at most once per connection and
+    /// never panics, so it's safe to call from `Drop` during unwinding.
+    fn release(&mut self) -> Result<(), SpnavError> {
+        if self.closed {
+            return Ok(());
+        }
+        self.closed = true;
+        let mut link = self
+            .backend
+            .link()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        link.count = link.count.saturating_sub(1);
+        if link.count == 0 && link.open {
+            // The link is unusable either way, so it counts as closed
+            // even if close fails.
+            link.open = false;
+            self.backend.close()
+        } else {
+            Ok(())
+        }
     }
 
-    pub fn wait(&self) -> Result<Event, ()> {
-        lib::spnav_wait_event()
+    fn take_lookahead(&self) -> Option<Event> {
+        self.lookahead.lock().expect("to lock").pop_front()
+    }
+
+    fn clear_lookahead(&self) {
+        self.lookahead.lock().expect("to lock").clear();
+    }
+
+    /// Applies the connection's options to a freshly read event, returning
+    /// `None` if it should be dropped.
+    fn process(&self, event: Event) -> Option<Event> {
+        if self.is_paused() {
+            return None;
+        }
+        match event {
+            Event::Motion(m) if self.options.skip_zero_motion && m.is_zero() => None,
+            Event::Motion(mut m) => {
+                if let Some(gains) = self.options.motion_gains() {
+                    m.scale_axes(gains);
+                }
+                Some(Event::Motion(m))
+            }
+            event => Some(event),
+        }
+    }
+
+    fn take_synthetic(&self) -> Option<Event> {
+        #[cfg(feature = "test-inject")]
+        return self.synthetic.lock().expect("to lock").pop_front();
+        #[cfg(not(feature = "test-inject"))]
+        None
+    }
+
+    fn read_poll(&self) -> Option<Event> {
+        loop {
+            let event = match self.take_synthetic() {
+                Some(event) => event,
+                None => self.backend.poll()?,
+            };
+            if let Some(event) = self.process(event) {
+                return Some(event);
+            }
+        }
+    }
+
+    fn read_wait(&self) -> Result<Event, SpnavError> {
+        loop {
+            let event = match self.take_synthetic() {
+                Some(event) => event,
+                None => self.backend.wait().map_err(|e| {
+                    if self.backend.link_lost() {
+                        SpnavError::ConnectionLost
+                    } else {
+                        e
+                    }
+                })?,
+            };
+            if let Some(event) = self.process(event) {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+/// A clone shares the libspnav link, which stays open until the last
+/// clone is closed or dropped. Clones read from the same queue, so each
+/// event goes to whichever clone polls first. The lookahead buffer is not
+/// shared: the clone starts with an empty one.
+impl Clone for Connection {
+    fn clone(&self) -> Self {
+        self.backend
+            .link()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .count += 1;
+        Connection {
+            fd: self.fd,
+            backend: self.backend.clone(),
+            options: self.options.clone(),
+            lookahead: Mutex::default(),
+            #[cfg(feature = "test-inject")]
+            synthetic: Mutex::default(),
+            paused: AtomicBool::new(self.is_paused()),
+            resume_mask: AtomicU32::new(self.resume_mask.load(Ordering::SeqCst)),
+            closed: false,
+        }
     }
 }
 
 impl Drop for Connection {
     fn drop(&mut self) {
-        if let Some(conn_count) = CONN_COUNT.get() {
-            let mut count = conn_count.lock().expect("to lock");
-            if *count == 1 {
-                *count = 0;
-                lib::spnav_close().expect("to close");
-            } else {
-                *count -= 1;
-            }
+        if let Err(_e) = self.release() {
+            #[cfg(feature = "log")]
+            log::warn!("{}", _e);
         }
     }
 }
@@ -148,55 +1130,55 @@ impl Drop for Connection {
 pub mod lib {
     use super::*;
 
-    pub fn spnav_open() -> Result<(), ()> {
+    pub fn spnav_open() -> Result<(), SpnavError> {
         unsafe {
             if libspnav::spnav_open() == -1 {
-                Err(())
+                Err(SpnavError::Open)
             } else {
                 Ok(())
             }
         }
     }
 
-    pub fn spnav_close() -> Result<(), ()> {
+    pub fn spnav_close() -> Result<(), SpnavError> {
         unsafe {
             if libspnav::spnav_close() == -1 {
-                Err(())
+                Err(SpnavError::Close)
             } else {
                 Ok(())
             }
         }
     }
 
-    pub fn spnav_fd() -> Result<i32, ()> {
+    pub fn spnav_fd() -> Result<i32, SpnavError> {
         unsafe {
             let fd = libspnav::spnav_fd();
             if fd == -1 {
-                Err(())
+                Err(SpnavError::Fd)
             } else {
                 Ok(fd)
             }
         }
     }
 
-    pub fn spnav_sensitivity(sens: f64) -> Result<i32, ()> {
+    pub fn spnav_sensitivity(sens: f64) -> Result<i32, SpnavError> {
         unsafe {
             let v = libspnav::spnav_sensitivity(sens);
             if v == -1 {
-                Err(())
+                Err(SpnavError::Sensitivity)
             } else {
                 Ok(v)
             }
         }
     }
 
-    pub fn spnav_wait_event() -> Result<Event, ()> {
+    pub fn spnav_wait_event() -> Result<Event, SpnavError> {
         let mut event = libspnav::spnav_event {
             type_: SPNAV_EVENT_ANY,
         };
         let t = unsafe { libspnav::spnav_wait_event(&mut event) };
         if t == 0 {
-            Err(())
+            Err(SpnavError::Wait)
         } else {
             event.try_into()
         }
@@ -214,6 +1196,180 @@ pub mod lib {
         }
     }
 
+    /// libspnav 1.x functions missing from the 0.x bindings. Each is only
+    /// declared when `build.rs` found it in the linked library.
+    mod ffi {
+        extern "C" {
+            #[cfg(spnav_has_client_name)]
+            pub fn spnav_client_name(name: *const std::os::raw::c_char) -> std::os::raw::c_int;
+            #[cfg(spnav_has_dev_name)]
+            pub fn spnav_dev_name(
+                buf: *mut std::os::raw::c_char,
+                bufsz: std::os::raw::c_int,
+            ) -> std::os::raw::c_int;
+            #[cfg(spnav_has_evmask)]
+            pub fn spnav_evmask(mask: std::os::raw::c_uint) -> std::os::raw::c_int;
+            #[cfg(spnav_has_protocol)]
+            pub fn spnav_protocol() -> std::os::raw::c_int;
+            #[cfg(spnav_has_dev_buttons)]
+            pub fn spnav_dev_buttons() -> std::os::raw::c_int;
+            #[cfg(spnav_has_dev_axes)]
+            pub fn spnav_dev_axes() -> std::os::raw::c_int;
+            #[cfg(spnav_has_cfg_get_sens)]
+            pub fn spnav_cfg_get_sens() -> std::os::raw::c_float;
+        }
+    }
+
+    // The wrappers below report `SpnavError::Unsupported` when the linked
+    // libspnav lacks the symbol.
+
+    pub fn spnav_client_name(name: &str) -> Result<(), SpnavError> {
+        #[cfg(spnav_has_client_name)]
+        {
+            let name = std::ffi::CString::new(name).map_err(|_| SpnavError::Query)?;
+            if unsafe { ffi::spnav_client_name(name.as_ptr()) } == -1 {
+                return Err(SpnavError::Query);
+            }
+            Ok(())
+        }
+        #[cfg(not(spnav_has_client_name))]
+        {
+            let _ = name;
+            Err(SpnavError::Unsupported)
+        }
+    }
+
+    /// Event mask selecting motion and button events.
+    pub const SPNAV_EVMASK_INPUT: u32 = 0x03;
+
+    pub fn spnav_dev_name() -> Result<String, SpnavError> {
+        #[cfg(spnav_has_dev_name)]
+        {
+            let mut buf = [0 as std::os::raw::c_char; 256];
+            let n = unsafe { ffi::spnav_dev_name(buf.as_mut_ptr(), buf.len() as i32) };
+            if n < 0 {
+                return Err(SpnavError::Query);
+            }
+            // libspnav truncates long names but always NUL-terminates.
+            let name = unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) };
+            Ok(name.to_string_lossy().into_owned())
+        }
+        #[cfg(not(spnav_has_dev_name))]
+        Err(SpnavError::Unsupported)
+    }
+
+    /// The mask last set through [`spnav_evmask`]. libspnav can't report
+    /// its mask, so it is tracked here, starting from libspnav's default.
+    #[cfg(spnav_has_evmask)]
+    static EVMASK: AtomicU32 = AtomicU32::new(SPNAV_EVMASK_INPUT);
+
+    /// Sets the event mask, returning the previous one.
+    pub fn spnav_evmask(mask: u32) -> Result<u32, SpnavError> {
+        #[cfg(spnav_has_evmask)]
+        {
+            if unsafe { ffi::spnav_evmask(mask) } == -1 {
+                return Err(SpnavError::Query);
+            }
+            Ok(EVMASK.swap(mask, Ordering::SeqCst))
+        }
+        #[cfg(not(spnav_has_evmask))]
+        {
+            let _ = mask;
+            Err(SpnavError::Unsupported)
+        }
+    }
+
+    pub fn spnav_protocol() -> Result<i32, SpnavError> {
+        #[cfg(spnav_has_protocol)]
+        {
+            match unsafe { ffi::spnav_protocol() } {
+                -1 => Err(SpnavError::Query),
+                v => Ok(v),
+            }
+        }
+        #[cfg(not(spnav_has_protocol))]
+        Err(SpnavError::Unsupported)
+    }
+
+    pub fn spnav_dev_buttons() -> Result<usize, SpnavError> {
+        #[cfg(spnav_has_dev_buttons)]
+        {
+            let n = unsafe { ffi::spnav_dev_buttons() };
+            usize::try_from(n).map_err(|_| SpnavError::Query)
+        }
+        #[cfg(not(spnav_has_dev_buttons))]
+        Err(SpnavError::Unsupported)
+    }
+
+    pub fn spnav_dev_axes() -> Result<usize, SpnavError> {
+        #[cfg(spnav_has_dev_axes)]
+        {
+            let n = unsafe { ffi::spnav_dev_axes() };
+            usize::try_from(n).map_err(|_| SpnavError::Query)
+        }
+        #[cfg(not(spnav_has_dev_axes))]
+        Err(SpnavError::Unsupported)
+    }
+
+    pub fn spnav_cfg_get_sens() -> Result<f64, SpnavError> {
+        #[cfg(spnav_has_cfg_get_sens)]
+        {
+            match unsafe { ffi::spnav_cfg_get_sens() } {
+                s if s < 0.0 => Err(SpnavError::Query),
+                s => Ok(s.into()),
+            }
+        }
+        #[cfg(not(spnav_has_cfg_get_sens))]
+        Err(SpnavError::Unsupported)
+    }
+
+    /// Sets or clears `O_NONBLOCK` on `fd`.
+    pub fn set_nonblocking(fd: i32, nb: bool) -> Result<(), SpnavError> {
+        let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+        if flags == -1 {
+            return Err(SpnavError::Fd);
+        }
+        let flags = if nb {
+            flags | libc::O_NONBLOCK
+        } else {
+            flags & !libc::O_NONBLOCK
+        };
+        if unsafe { libc::fcntl(fd, libc::F_SETFL, flags) } == -1 {
+            return Err(SpnavError::Fd);
+        }
+        Ok(())
+    }
+
+    /// Whether `poll(2)` reports `fd` as hung up or invalid.
+    pub fn hung_up(fd: i32) -> bool {
+        let mut pfd = libc::pollfd {
+            fd,
+            events: 0,
+            revents: 0,
+        };
+        let n = unsafe { libc::poll(&mut pfd, 1, 0) };
+        n > 0 && pfd.revents & (libc::POLLHUP | libc::POLLERR | libc::POLLNVAL) != 0
+    }
+
+    /// Waits with `poll(2)` until `fd` is readable or `timeout` passes.
+    pub fn wait_readable(fd: i32, timeout: Duration) -> Result<bool, SpnavError> {
+        let mut pfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ms = timeout.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+        loop {
+            let n = unsafe { libc::poll(&mut pfd, 1, ms) };
+            if n >= 0 {
+                return Ok(n > 0);
+            }
+            if std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                return Err(SpnavError::Wait);
+            }
+        }
+    }
+
     pub fn spnav_remove_events(t: EventType) -> i32 {
         unsafe { libspnav::spnav_remove_events(t.into()) }
     }
@@ -224,10 +1380,520 @@ mod test {
     use super::*;
 
     #[test]
-    fn basic() -> Result<(), ()> {
+    fn basic() -> Result<(), SpnavError> {
         let c = Connection::new()?;
         println!("{:?}", c);
         println!("{:?}", c.wait());
         Ok(())
     }
+
+    fn motion(x: i32) -> Event {
+        Event::Motion(MotionEvent::new([x, 0, 0, 0, 0, 0], 16))
+    }
+
+    fn button(bnum: i32, press: bool) -> Event {
+        Event::Button(ButtonEvent { press, bnum })
+    }
+
+    #[test]
+    fn null_connection_yields_nothing() {
+        let c = Connection::new_mock(None);
+        assert_eq!(c.protocol(), Protocol::Mock);
+        assert!(c.poll().is_none());
+        assert_eq!(c.wait().unwrap_err(), SpnavError::Wait);
+    }
+
+    #[test]
+    fn peek_keeps_event_for_drain() {
+        let c = Connection::new_mock([button(1, true), motion(3)]);
+        assert_eq!(c.peek_type(), Some(EventType::Button));
+        assert_eq!(c.peek_type(), Some(EventType::Button));
+        let events = c.drain();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            events[0],
+            Event::Button(ButtonEvent { bnum: 1, .. })
+        ));
+        assert_eq!(c.peek_type(), None);
+    }
+
+    #[test]
+    fn zero_motion_forwarded_by_default() {
+        let c = Connection::new_mock([motion(0), motion(2)]);
+        assert_eq!(c.drain().len(), 2);
+    }
+
+    #[test]
+    fn zero_motion_skipped_when_enabled() {
+        let c = Connection::builder().skip_zero_motion(true).open_mock([
+            motion(0),
+            motion(2),
+            motion(0),
+        ]);
+        assert_eq!(c.peek_type(), Some(EventType::Motion));
+        let events = c.drain();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], Event::Motion(MotionEvent { x: 2, .. })));
+    }
+
+    #[test]
+    fn axes_match_indices() {
+        let mut m = MotionEvent::new([1, 2, 3, 4, 5, 6], 0);
+        assert_eq!(m.axes()[TZ], 3);
+        assert_eq!(m.axes()[RX], 4);
+        m.set_axis(RZ, -6);
+        assert_eq!(m.rz, -6);
+        assert_eq!(m.axes(), [1, 2, 3, 4, 5, -6]);
+    }
+
+    #[test]
+    fn merge_sums_axes_and_period() {
+        let a = MotionEvent::new([1, 2, 3, -1, -2, -3], 8);
+        let m = a.merge(&a);
+        assert_eq!(m.axes(), [2, 4, 6, -2, -4, -6]);
+        assert_eq!(m.period, 16);
+    }
+
+    #[test]
+    fn merge_saturates() {
+        let mut a = MotionEvent::new([i32::MAX - 1, i32::MIN + 1, 0, 0, 0, 0], u32::MAX);
+        let mut b = a.clone();
+        b.x = 10;
+        b.y = -10;
+        let m = a.merge(&b);
+        assert_eq!(m.x, i32::MAX);
+        assert_eq!(m.y, i32::MIN);
+        assert_eq!(m.period, u32::MAX);
+        a.x = i32::MAX - 5;
+        assert_eq!(a.merge(&MotionEvent { x: 5, ..b }).x, i32::MAX);
+    }
+
+    #[test]
+    fn merge_and_is_zero_see_extra_axes() {
+        let mut m = MotionEvent::new([0; 6], 8);
+        m.extra = vec![1, 2];
+        assert!(!m.is_zero());
+        let n = MotionEvent {
+            extra: vec![i32::MAX],
+            ..m.clone()
+        };
+        assert_eq!(m.merge(&n).extra, [i32::MAX, 2]);
+        m.extra = vec![0];
+        assert!(m.is_zero());
+    }
+
+    #[test]
+    fn event_layout() {
+        use std::mem::{offset_of, size_of};
+        const _: () = {
+            assert!(EVENT_SIZE == size_of::<libspnav::spnav_event_motion>());
+            assert!(EVENT_SIZE >= size_of::<libspnav::spnav_event_button>());
+            assert!(offset_of!(libspnav::spnav_event_motion, type_) == 0);
+            assert!(offset_of!(libspnav::spnav_event_button, type_) == 0);
+        };
+    }
+
+    fn mock_client_name(c: &Connection) -> Option<String> {
+        match &c.backend {
+            Backend::Mock(m) => m.client_name.lock().unwrap().clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn client_name_registered_on_open() {
+        let c = Connection::builder().client_name("MyApp").open_mock(None);
+        assert_eq!(mock_client_name(&c).as_deref(), Some("MyApp"));
+    }
+
+    #[test]
+    fn client_name_defaults_to_exe_stem() {
+        let c = Connection::new_mock(None);
+        let stem = std::env::current_exe().unwrap();
+        let stem = stem.file_stem().unwrap().to_str().unwrap();
+        assert_eq!(mock_client_name(&c).as_deref(), Some(stem));
+    }
+
+    fn mock_sensitivity(c: &Connection) -> Option<f64> {
+        match &c.backend {
+            Backend::Mock(m) => *m.sensitivity.lock().unwrap(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn sensitivity_percent_applied_on_open() {
+        let c = Connection::new_mock(None);
+        assert_eq!(mock_sensitivity(&c), None);
+        let c = Connection::builder()
+            .sensitivity_percent(100.0)
+            .open_mock(None);
+        assert_eq!(mock_sensitivity(&c), Some(4.0));
+    }
+
+    #[test]
+    fn paused_events_are_discarded() {
+        let c = Connection::new_mock([motion(1), motion(2), motion(3)]);
+        c.peek_type();
+        c.pause();
+        assert!(c.is_paused());
+        assert!(c.poll().is_none());
+        c.resume();
+        assert!(c.poll().is_none());
+    }
+
+    #[test]
+    fn pause_restores_previous_mask() {
+        let mock = std::sync::Arc::new(mock::Mock::new([motion(1)]).with_evmask(0x01));
+        let c = ConnectionBuilder::new().open_backend(Backend::Mock(mock.clone()));
+        let mask = || *mock.evmask.lock().unwrap();
+        // Resuming a connection that isn't paused keeps live input.
+        c.resume();
+        assert!(c.poll().is_some());
+        c.pause();
+        c.pause();
+        assert_eq!(mask(), Some(0));
+        c.resume();
+        assert_eq!(mask(), Some(0x01));
+    }
+
+    #[test]
+    fn resume_discards_backlog() {
+        let c = Connection::new_mock(None);
+        c.pause();
+        let Backend::Mock(mock) = &c.backend else {
+            unreachable!()
+        };
+        mock.push(motion(1));
+        mock.push(button(0, true));
+        c.resume();
+        assert!(c.poll().is_none());
+        mock.push(motion(2));
+        assert!(matches!(
+            c.poll(),
+            Some(Event::Motion(MotionEvent { x: 2, .. }))
+        ));
+    }
+
+    #[test]
+    fn local_sensitivity_scales_without_daemon() {
+        let c = Connection::builder()
+            .local_sensitivity(0.5)
+            .open_mock([motion(100), motion(-7)]);
+        let xs: Vec<i32> = c
+            .drain()
+            .into_iter()
+            .map(|e| match e {
+                Event::Motion(m) => m.x,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(xs, [50, -4]);
+        assert_eq!(mock_sensitivity(&c), None);
+    }
+
+    #[test]
+    fn motion_pending_keeps_events() {
+        let c = Connection::new_mock([button(0, true), motion(0), motion(4), button(0, false)]);
+        assert!(c.any_motion_pending());
+        let kinds: Vec<i32> = c.drain().iter().map(|e| e.encode().kind).collect();
+        assert_eq!(kinds, [2, 1, 1, 2]);
+
+        let c = Connection::new_mock([motion(0), button(1, true)]);
+        assert!(!c.any_motion_pending());
+        assert_eq!(c.drain().len(), 2);
+        assert!(!c.any_motion_pending());
+    }
+
+    #[test]
+    fn poll_into_overwrites() {
+        let c = Connection::new_mock([motion(3)]);
+        let mut ev = button(1, true);
+        assert!(c.poll_into(&mut ev));
+        assert!(matches!(ev, Event::Motion(MotionEvent { x: 3, .. })));
+        assert!(!c.poll_into(&mut ev));
+        assert!(matches!(ev, Event::Motion(MotionEvent { x: 3, .. })));
+    }
+
+    #[test]
+    fn drain_on_ready_empties_queue() {
+        let c = Connection::new_mock((0..5).map(motion));
+        assert_eq!(c.peek_type(), Some(EventType::Motion));
+        assert_eq!(c.drain_on_ready().len(), 5);
+        assert!(c.poll().is_none());
+    }
+
+    #[test]
+    fn flush_clears_lookahead_and_queue() {
+        let c = Connection::new_mock([
+            motion(1),
+            button(0, true),
+            motion(2),
+            button(0, false),
+            motion(3),
+        ]);
+        assert_eq!(c.peek_type(), Some(EventType::Motion));
+        assert_eq!(c.flush(EventType::Motion), 3);
+        let left: Vec<EventType> = c.drain().iter().map(Event::event_type).collect();
+        assert_eq!(left, [EventType::Button, EventType::Button]);
+
+        // As with libspnav, removing Any removes nothing, so this empties
+        // the queue by polling.
+        let c = Connection::new_mock([button(1, true), motion(1), motion(2)]);
+        assert_eq!(c.peek_type(), Some(EventType::Button));
+        assert_eq!(c.flush(EventType::Any), 3);
+        assert!(c.poll().is_none());
+
+        #[cfg(feature = "test-inject")]
+        {
+            let c = Connection::new_mock([motion(1)]);
+            c.push_synthetic(button(0, true));
+            c.push_synthetic(motion(2));
+            assert_eq!(c.flush(EventType::Button), 1);
+            assert_eq!(c.flush(EventType::Any), 2);
+            assert!(c.poll().is_none());
+        }
+    }
+
+    #[cfg(feature = "test-inject")]
+    #[test]
+    fn synthetic_events_come_first() {
+        let c = Connection::builder()
+            .local_sensitivity(2.0)
+            .open_mock([button(0, true)]);
+        c.push_synthetic(motion(5));
+        assert!(matches!(
+            c.wait(),
+            Ok(Event::Motion(MotionEvent { x: 10, .. }))
+        ));
+        assert!(matches!(c.poll(), Some(Event::Button(_))));
+        assert!(c.poll().is_none());
+    }
+
+    #[test]
+    fn rotation_gain_only_scales_rotation() {
+        let m = MotionEvent::new([10, -20, 30, 40, -50, 60], 16);
+        let c = Connection::builder()
+            .rotation_gain(2.0)
+            .open_mock([Event::Motion(m)]);
+        match c.poll() {
+            Some(Event::Motion(m)) => assert_eq!(m.axes(), [10, -20, 30, 80, -100, 120]),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn model_from_device_name() {
+        let c = Connection::new_mock(None);
+        assert_eq!(c.model(), Err(SpnavError::Unsupported));
+        let mock = mock::Mock::new(None).with_device_name("3Dconnexion SpaceMouse Pro");
+        let c = ConnectionBuilder::new().open_backend(Backend::Mock(mock.into()));
+        assert_eq!(c.model(), Ok(DeviceModel::SpaceMousePro));
+    }
+
+    #[test]
+    fn wait_batch_takes_queued_events() {
+        let c = Connection::new_mock((0..5).map(motion));
+        let timeout = Duration::from_millis(10);
+        assert_eq!(c.wait_batch(3, timeout).unwrap().len(), 3);
+        assert_eq!(c.wait_batch(3, timeout).unwrap().len(), 2);
+        assert!(c.wait_batch(3, timeout).unwrap().is_empty());
+    }
+
+    #[test]
+    fn explicit_close() {
+        let c = Connection::new_mock(None);
+        assert_eq!(c.close(), Ok(()));
+    }
+
+    #[test]
+    fn nonblocking_fd() {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let mut c = Connection::new_mock(None);
+        assert_eq!(c.set_nonblocking(true), Err(SpnavError::Fd));
+        c.fd = fds[0];
+        c.set_nonblocking(true).unwrap();
+        // The mock never reads the fd, so check the descriptor itself: a
+        // read on the empty pipe fails at once instead of blocking.
+        let mut byte = 0u8;
+        let n = unsafe { libc::read(c.fd, (&mut byte as *mut u8).cast(), 1) };
+        assert_eq!(n, -1);
+        assert_eq!(
+            std::io::Error::last_os_error().kind(),
+            std::io::ErrorKind::WouldBlock
+        );
+        c.set_nonblocking(false).unwrap();
+        let flags = unsafe { libc::fcntl(c.fd, libc::F_GETFL) };
+        assert_eq!(flags & libc::O_NONBLOCK, 0);
+        unsafe {
+            libc::close(fds[0]);
+            libc::close(fds[1]);
+        }
+    }
+
+    #[test]
+    fn clones_close_once() {
+        let c = Connection::new_mock(None);
+        let Backend::Mock(mock) = c.backend.clone() else {
+            unreachable!()
+        };
+        let mut clones: Vec<Option<Connection>> = (0..5).map(|_| Some(c.clone())).collect();
+        clones.push(Some(c));
+        assert_eq!(mock.links.lock().unwrap().count, 6);
+        for i in [3, 0, 5, 1, 4] {
+            clones[i] = None;
+        }
+        assert_eq!(mock.closes.load(Ordering::Relaxed), 0);
+        assert_eq!(clones[2].take().unwrap().close(), Ok(()));
+        assert_eq!(mock.closes.load(Ordering::Relaxed), 1);
+        assert_eq!(mock.links.lock().unwrap().count, 0);
+    }
+
+    #[test]
+    fn reopens_after_last_drop() {
+        let mock = std::sync::Arc::new(mock::Mock::new(None));
+        let open = || Connection::open_on(Backend::Mock(mock.clone()), Default::default());
+        let opens = || mock.opens.load(Ordering::Relaxed);
+        let closes = || mock.closes.load(Ordering::Relaxed);
+
+        mock.fail_open.store(true, Ordering::Relaxed);
+        assert_eq!(open().unwrap_err(), SpnavError::Open);
+        assert_eq!(mock.links.lock().unwrap().count, 0);
+        mock.fail_open.store(false, Ordering::Relaxed);
+
+        let c = open().unwrap();
+        let c2 = open().unwrap();
+        assert_eq!(opens(), 1);
+        drop(c);
+        drop(c2);
+        assert_eq!(closes(), 1);
+
+        let c = open().unwrap();
+        assert_eq!((opens(), mock.links.lock().unwrap().count), (2, 1));
+        drop(c);
+        assert_eq!(closes(), 2);
+    }
+
+    #[test]
+    fn clone_after_failed_reconnect_closes_nothing() {
+        let mock = std::sync::Arc::new(mock::Mock::new(None));
+        let mut c = Connection::open_on(Backend::Mock(mock.clone()), Default::default()).unwrap();
+        let closes = || mock.closes.load(Ordering::Relaxed);
+
+        mock.fail_open.store(true, Ordering::Relaxed);
+        assert_eq!(c.reconnect(), Err(SpnavError::Open));
+        assert_eq!(closes(), 1);
+        let c2 = c.clone();
+        drop(c);
+        drop(c2);
+        assert_eq!(closes(), 1);
+        assert_eq!(mock.links.lock().unwrap().count, 0);
+
+        mock.fail_open.store(false, Ordering::Relaxed);
+        let c = Connection::open_on(Backend::Mock(mock.clone()), Default::default()).unwrap();
+        assert_eq!(mock.opens.load(Ordering::Relaxed), 2);
+        drop(c);
+        assert_eq!(closes(), 2);
+    }
+
+    #[test]
+    fn lost_link_reported() {
+        let c = Connection::new_mock([button(0, true), button(0, false)]);
+        let Backend::Mock(mock) = c.backend.clone() else {
+            unreachable!()
+        };
+        assert!(c.wait().is_ok());
+        mock.lost.store(true, Ordering::Relaxed);
+        assert_eq!(c.wait().unwrap_err(), SpnavError::ConnectionLost);
+        assert_eq!(c.try_poll().unwrap_err(), SpnavError::ConnectionLost);
+        assert_eq!(
+            c.wait_timeout(Duration::from_millis(1)).unwrap_err(),
+            SpnavError::ConnectionLost
+        );
+        mock.lost.store(false, Ordering::Relaxed);
+        assert!(c.wait().is_ok());
+    }
+
+    fn raw_event(type_: i32, payload: [i32; 7]) -> libspnav::spnav_event {
+        // Initialise through the largest member so every byte of the union
+        // is defined whichever member is read back.
+        libspnav::spnav_event {
+            motion: libspnav::spnav_event_motion {
+                type_,
+                x: payload[0],
+                y: payload[1],
+                z: payload[2],
+                rx: payload[3],
+                ry: payload[4],
+                rz: payload[5],
+                period: payload[6] as u32,
+                data: std::ptr::null_mut(),
+            },
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn try_from_classifies_any_type(
+            type_ in proptest::prop_oneof![-2..5, proptest::num::i32::ANY],
+            payload: [i32; 7],
+        ) {
+            match Event::try_from(raw_event(type_, payload)) {
+                Ok(Event::Motion(m)) => {
+                    proptest::prop_assert_eq!(type_, SPNAV_EVENT_MOTION);
+                    proptest::prop_assert_eq!(&m.axes()[..], &payload[..6]);
+                    proptest::prop_assert_eq!(m.period, payload[6] as u32);
+                }
+                Ok(Event::Button(b)) => {
+                    proptest::prop_assert_eq!(type_, SPNAV_EVENT_BUTTON);
+                    proptest::prop_assert_eq!(b.press, payload[0] != 0);
+                    proptest::prop_assert_eq!(b.bnum, payload[1]);
+                }
+                Err(e) => {
+                    proptest::prop_assert_eq!(e, SpnavError::UnknownEventType(type_));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn raw_round_trip_motion() {
+        let ev = Event::Motion(MotionEvent::new([1, -2, 3, -4, 5, -6], 16));
+        match Event::decode(ev.encode()) {
+            Ok(Event::Motion(m)) => {
+                assert_eq!(m.t(), (1, -2, 3));
+                assert_eq!(m.r(), (-4, 5, -6));
+                assert_eq!(m.period, 16);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn raw_round_trip_button() {
+        let ev = Event::Button(ButtonEvent {
+            press: true,
+            bnum: 7,
+        });
+        match Event::decode(ev.encode()) {
+            Ok(Event::Button(b)) => {
+                assert!(b.press);
+                assert_eq!(b.bnum, 7);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn raw_decode_unknown() {
+        let raw = EventRaw {
+            kind: 42,
+            ..Default::default()
+        };
+        assert!(matches!(
+            Event::decode(raw),
+            Err(SpnavError::UnknownEventType(42))
+        ));
+    }
 }
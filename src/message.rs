@@ -0,0 +1,97 @@
+use crate::{ButtonEvent, Event, MotionEvent, SpnavError, SPNAV_EVENT_BUTTON, SPNAV_EVENT_MOTION};
+use std::convert::TryFrom;
+
+/// Flat form of an [`Event`] that maps one-to-one onto a protobuf message.
+///
+/// `kind` is the libspnav event type, 1 for motion and 2 for buttons, as
+/// in [`EventRaw::kind`](crate::EventRaw::kind). Fields that don't apply to the kind are zero,
+/// or `false`, like unset protobuf fields. [Extra axes](MotionEvent::extra)
+/// are dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EventMessage {
+    pub kind: i32,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub rx: i32,
+    pub ry: i32,
+    pub rz: i32,
+    pub period: u32,
+    pub button: i32,
+    pub press: bool,
+}
+
+impl From<Event> for EventMessage {
+    fn from(ev: Event) -> Self {
+        match ev {
+            Event::Motion(m) => EventMessage {
+                kind: SPNAV_EVENT_MOTION,
+                x: m.x,
+                y: m.y,
+                z: m.z,
+                rx: m.rx,
+                ry: m.ry,
+                rz: m.rz,
+                period: m.period,
+                ..Default::default()
+            },
+            Event::Button(b) => EventMessage {
+                kind: SPNAV_EVENT_BUTTON,
+                button: b.bnum,
+                press: b.press,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl TryFrom<EventMessage> for Event {
+    type Error = SpnavError;
+
+    /// Fails with [`SpnavError::UnknownEventType`] for any other `kind`.
+    fn try_from(msg: EventMessage) -> Result<Self, Self::Error> {
+        match msg.kind {
+            SPNAV_EVENT_MOTION => Ok(Event::Motion(MotionEvent::new(
+                [msg.x, msg.y, msg.z, msg.rx, msg.ry, msg.rz],
+                msg.period,
+            ))),
+            SPNAV_EVENT_BUTTON => Ok(Event::Button(ButtonEvent {
+                press: msg.press,
+                bnum: msg.button,
+            })),
+            kind => Err(SpnavError::UnknownEventType(kind)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn motion_round_trip() {
+        let ev = Event::Motion(MotionEvent::new([1, -2, 3, -4, 5, -6], 16));
+        let msg = EventMessage::from(ev.clone());
+        assert_eq!((msg.kind, msg.rz, msg.button), (SPNAV_EVENT_MOTION, -6, 0));
+        assert_eq!(Event::try_from(msg).unwrap().encode(), ev.encode());
+    }
+
+    #[test]
+    fn button_round_trip() {
+        let ev = Event::Button(ButtonEvent {
+            press: true,
+            bnum: 9,
+        });
+        let msg = EventMessage::from(ev.clone());
+        assert_eq!(
+            (msg.kind, msg.button, msg.press, msg.x),
+            (SPNAV_EVENT_BUTTON, 9, true, 0)
+        );
+        assert_eq!(Event::try_from(msg).unwrap().encode(), ev.encode());
+        let bad = EventMessage { kind: 7, ..msg };
+        assert_eq!(
+            Event::try_from(bad).unwrap_err(),
+            SpnavError::UnknownEventType(7)
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{Event, EventType, Link, SpnavError};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// In-memory stand-in for libspnav that hands out a fixed list of events.
+///
+/// `links` tracks the connections sharing it, like `LINK` does for
+/// libspnav; `opens` and `closes` count how often the link was opened and
+/// closed. Setting `fail_open` makes opening fail like a missing daemon,
+/// and `lost` makes the link look dead: nothing more is read from it.
+#[derive(Debug, Default)]
+pub(crate) struct Mock {
+    events: Mutex<VecDeque<Event>>,
+    pub(crate) links: Mutex<Link>,
+    pub(crate) opens: AtomicUsize,
+    pub(crate) closes: AtomicUsize,
+    pub(crate) fail_open: AtomicBool,
+    pub(crate) lost: AtomicBool,
+    pub(crate) client_name: Mutex<Option<String>>,
+    pub(crate) sensitivity: Mutex<Option<f64>>,
+    pub(crate) device_name: Option<String>,
+    pub(crate) num_buttons: Option<usize>,
+    pub(crate) num_axes: Option<usize>,
+    pub(crate) protocol_version: Option<i32>,
+    /// The event mask, or `None` if masks are unsupported.
+    pub(crate) evmask: Mutex<Option<u32>>,
+}
+
+impl Mock {
+    pub(crate) fn new(events: impl IntoIterator<Item = Event>) -> Self {
+        Mock {
+            events: Mutex::new(events.into_iter().collect()),
+            links: Mutex::default(),
+            opens: AtomicUsize::new(0),
+            closes: AtomicUsize::new(0),
+            fail_open: AtomicBool::new(false),
+            lost: AtomicBool::new(false),
+            client_name: Mutex::new(None),
+            sensitivity: Mutex::new(None),
+            device_name: None,
+            num_buttons: None,
+            num_axes: None,
+            protocol_version: None,
+            evmask: Mutex::new(None),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_device_name(mut self, name: &str) -> Self {
+        self.device_name = Some(name.to_owned());
+        self
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_device(mut self, buttons: usize, axes: usize, protocol: i32) -> Self {
+        self.num_buttons = Some(buttons);
+        self.num_axes = Some(axes);
+        self.protocol_version = Some(protocol);
+        self
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_evmask(self, mask: u32) -> Self {
+        *self.evmask.lock().expect("to lock") = Some(mask);
+        self
+    }
+
+    pub(crate) fn open(&self) -> Result<(), SpnavError> {
+        if self.fail_open.load(Ordering::Relaxed) {
+            return Err(SpnavError::Open);
+        }
+        self.opens.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn push(&self, ev: Event) {
+        self.events.lock().expect("to lock").push_back(ev);
+    }
+
+    pub(crate) fn poll(&self) -> Option<Event> {
+        if self.lost.load(Ordering::Relaxed) {
+            return None;
+        }
+        self.events.lock().expect("to lock").pop_front()
+    }
+
+    /// Drops queued events of type `t`, like `spnav_remove_events`, which
+    /// matches types exactly and so removes nothing for
+    /// [`EventType::Any`].
+    pub(crate) fn remove_events(&self, t: EventType) -> usize {
+        let mut events = self.events.lock().expect("to lock");
+        let before = events.len();
+        events.retain(|e| t == EventType::Any || e.event_type() != t);
+        before - events.len()
+    }
+
+    /// Never blocks: an empty mock reports `SpnavError::Wait` instead.
+    pub(crate) fn wait(&self) -> Result<Event, SpnavError> {
+        self.poll().ok_or(SpnavError::Wait)
+    }
+}
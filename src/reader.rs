@@ -0,0 +1,68 @@
+use crate::{Connection, Event};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+impl Connection {
+    /// Moves the connection onto a thread that forwards every event into
+    /// an unbounded channel.
+    ///
+    /// The thread exits, dropping the connection, when `wait` fails or
+    /// when it tries to send an event after the receiver has been dropped.
+    /// Since it spends its time blocked in `wait`, dropping the receiver
+    /// only stops it once the next event arrives.
+    pub fn spawn_reader(self) -> (JoinHandle<()>, mpsc::Receiver<Event>) {
+        let (tx, rx) = mpsc::channel();
+        let handle = std::thread::spawn(move || self.forward(|event| tx.send(event).is_ok()));
+        (handle, rx)
+    }
+
+    /// Like [`spawn_reader`](Self::spawn_reader), but over an unbounded
+    /// `crossbeam-channel`.
+    ///
+    /// The receiver can be cloned to share the work between consumers;
+    /// each event goes to exactly one of them. The thread stops under the
+    /// same conditions, once every clone of the receiver has been dropped.
+    #[cfg(feature = "crossbeam")]
+    pub fn spawn_reader_crossbeam(self) -> (JoinHandle<()>, crossbeam_channel::Receiver<Event>) {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let handle = std::thread::spawn(move || self.forward(|event| tx.send(event).is_ok()));
+        (handle, rx)
+    }
+
+    /// Passes events to `send` until it returns `false` or `wait` fails.
+    fn forward(self, mut send: impl FnMut(Event) -> bool) {
+        while let Ok(event) = self.wait() {
+            if !send(event) {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ButtonEvent;
+
+    fn buttons(n: i32) -> Connection {
+        Connection::new_mock((0..n).map(|bnum| Event::Button(ButtonEvent { press: true, bnum })))
+    }
+
+    #[test]
+    fn reader_forwards_until_wait_fails() {
+        let (handle, rx) = buttons(3).spawn_reader();
+        handle.join().unwrap();
+        assert_eq!(rx.iter().count(), 3);
+    }
+
+    #[cfg(feature = "crossbeam")]
+    #[test]
+    fn crossbeam_receivers_share_events() {
+        let (handle, rx) = buttons(4).spawn_reader_crossbeam();
+        handle.join().unwrap();
+        let other = rx.clone();
+        assert!(rx.recv().is_ok());
+        assert_eq!(other.iter().count(), 3);
+        assert!(rx.recv().is_err());
+    }
+}
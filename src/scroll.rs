@@ -0,0 +1,64 @@
+use crate::MotionEvent;
+
+/// One scroll-wheel step; the sign gives the direction, positive for a
+/// positive `z`. In spacenavd's frame, with z into the screen, that is
+/// pushing the cap forward, away from the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollTick(pub i32);
+
+/// Turns push forward/pull back on the `z` axis into discrete scroll
+/// steps.
+///
+/// `z` values are summed and a tick is emitted each time the sum moves a
+/// whole `step` away from zero; the remainder carries over to the next
+/// event, so a steady push gives evenly spaced ticks whatever the event
+/// rate. Pushing the other way first uses up the carried remainder.
+#[derive(Debug, Clone)]
+pub struct ScrollAccumulator {
+    step: i64,
+    acc: i64,
+}
+
+impl ScrollAccumulator {
+    /// Panics if `step` is not positive.
+    pub fn new(step: i32) -> Self {
+        assert!(step > 0, "scroll step must be positive");
+        ScrollAccumulator {
+            step: step.into(),
+            acc: 0,
+        }
+    }
+
+    pub fn feed(&mut self, ev: &MotionEvent) -> Vec<ScrollTick> {
+        self.acc += i64::from(ev.z);
+        let n = self.acc / self.step;
+        self.acc -= n * self.step;
+        let tick = ScrollTick(n.signum() as i32);
+        vec![tick; n.unsigned_abs() as usize]
+    }
+
+    pub fn reset(&mut self) {
+        self.acc = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn steady_push_ticks_evenly() {
+        let m = MotionEvent::new([0, 0, -40, 0, 0, 0], 16);
+        let mut s = ScrollAccumulator::new(100);
+        let ticks: Vec<usize> = (0..10).map(|_| s.feed(&m).len()).collect();
+        assert_eq!(ticks, [0, 0, 1, 0, 1, 0, 0, 1, 0, 1]);
+        assert_eq!(
+            s.feed(&MotionEvent {
+                z: -250,
+                ..m.clone()
+            }),
+            [ScrollTick(-1); 2]
+        );
+        assert!(s.feed(&MotionEvent { z: 90, ..m }).is_empty());
+    }
+}
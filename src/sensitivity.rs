@@ -0,0 +1,53 @@
+/// Maps a 0–100% slider position to a libspnav sensitivity multiplier.
+///
+/// The mapping is exponential so each 50% step scales by four: 0% gives
+/// 0.25×, 50% the default 1.0×, and 100% gives 4.0×. Inputs outside 0–100
+/// are clamped.
+pub fn sensitivity_from_percent(pct: f64) -> f64 {
+    4f64.powf((pct.clamp(0.0, 100.0) - 50.0) / 50.0)
+}
+
+/// Inverse of [`sensitivity_from_percent`], clamped to 0–100. Zero,
+/// negative and NaN multipliers give 0%.
+pub fn sensitivity_to_percent(mult: f64) -> f64 {
+    if mult.is_nan() || mult <= 0.0 {
+        return 0.0;
+    }
+    (50.0 + 50.0 * mult.log(4.0)).clamp(0.0, 100.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn endpoints_and_midpoint() {
+        assert!(close(sensitivity_from_percent(0.0), 0.25));
+        assert!(close(sensitivity_from_percent(50.0), 1.0));
+        assert!(close(sensitivity_from_percent(100.0), 4.0));
+        assert!(close(sensitivity_to_percent(0.25), 0.0));
+        assert!(close(sensitivity_to_percent(1.0), 50.0));
+        assert!(close(sensitivity_to_percent(4.0), 100.0));
+    }
+
+    #[test]
+    fn round_trip_and_clamp() {
+        assert!(close(
+            sensitivity_to_percent(sensitivity_from_percent(30.0)),
+            30.0
+        ));
+        assert!(close(sensitivity_from_percent(150.0), 4.0));
+        assert!(close(sensitivity_to_percent(100.0), 100.0));
+    }
+
+    #[test]
+    fn non_positive_multiplier_is_zero_percent() {
+        assert_eq!(sensitivity_to_percent(0.0), 0.0);
+        assert_eq!(sensitivity_to_percent(-2.0), 0.0);
+        assert_eq!(sensitivity_to_percent(f64::NAN), 0.0);
+    }
+}
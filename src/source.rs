@@ -0,0 +1,21 @@
+use crate::{Connection, Event, SpnavError};
+
+/// Anything events can be read from, such as a [`Connection`] or, with the
+/// `serde` feature, a `RemoteSource`.
+pub trait EventSource {
+    /// Returns the next event if one is available, without blocking.
+    fn poll(&mut self) -> Option<Event>;
+
+    /// Blocks until the next event arrives.
+    fn wait(&mut self) -> Result<Event, SpnavError>;
+}
+
+impl EventSource for Connection {
+    fn poll(&mut self) -> Option<Event> {
+        Connection::poll(self)
+    }
+
+    fn wait(&mut self) -> Result<Event, SpnavError> {
+        Connection::wait(self)
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{ButtonEvent, Connection, Event, MotionEvent};
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+/// Number of events each stream buffers before the reader blocks.
+pub const STREAM_CAPACITY: usize = 64;
+
+/// Motion half of [`Connection::split_streams`].
+#[derive(Debug)]
+pub struct MotionStream {
+    rx: mpsc::Receiver<MotionEvent>,
+}
+
+/// Button half of [`Connection::split_streams`].
+#[derive(Debug)]
+pub struct ButtonStream {
+    rx: mpsc::Receiver<ButtonEvent>,
+}
+
+impl MotionStream {
+    pub async fn recv(&mut self) -> Option<MotionEvent> {
+        self.rx.recv().await
+    }
+
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<MotionEvent>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl ButtonStream {
+    pub async fn recv(&mut self) -> Option<ButtonEvent> {
+        self.rx.recv().await
+    }
+
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<ButtonEvent>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl Connection {
+    /// Moves the connection onto a reader thread that routes motion and
+    /// button events into two independent streams.
+    ///
+    /// Each stream buffers up to [`STREAM_CAPACITY`] events. When one
+    /// consumer lags and its buffer fills, the reader blocks until it
+    /// catches up, which stalls the other stream as well. Dropping one
+    /// stream discards its events; the reader exits once both are dropped
+    /// (noticed on the next event) or when `wait` fails, which ends both
+    /// streams.
+    pub fn split_streams(self) -> (MotionStream, ButtonStream) {
+        let (motion_tx, motion_rx) = mpsc::channel(STREAM_CAPACITY);
+        let (button_tx, button_rx) = mpsc::channel(STREAM_CAPACITY);
+        std::thread::spawn(move || {
+            while let Ok(event) = self.wait() {
+                // A send only fails once that stream has been dropped.
+                match event {
+                    Event::Motion(m) => {
+                        let _ = motion_tx.blocking_send(m);
+                    }
+                    Event::Button(b) => {
+                        let _ = button_tx.blocking_send(b);
+                    }
+                }
+                if motion_tx.is_closed() && button_tx.is_closed() {
+                    break;
+                }
+            }
+        });
+        (
+            MotionStream { rx: motion_rx },
+            ButtonStream { rx: button_rx },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn routes_by_kind() {
+        let c = Connection::new_mock([
+            Event::Button(ButtonEvent {
+                press: true,
+                bnum: 1,
+            }),
+            Event::Motion(MotionEvent::new([5, 0, 0, 0, 0, 0], 8)),
+        ]);
+        let (mut motion, mut buttons) = c.split_streams();
+        assert_eq!(motion.recv().await.map(|m| m.x), Some(5));
+        assert_eq!(motion.recv().await.map(|m| m.x), None);
+        assert_eq!(buttons.recv().await.map(|b| b.bnum), Some(1));
+        assert!(buttons.recv().await.is_none());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::Event;
+use tracing::Span;
+
+/// A trace-level span named `$name` whose `kind` and `period` fields are
+/// filled in by [`record`] once an event has been read.
+macro_rules! event_span {
+    ($name:literal) => {
+        tracing::trace_span!(
+            $name,
+            kind = tracing::field::Empty,
+            period = tracing::field::Empty
+        )
+    };
+}
+pub(crate) use event_span;
+
+/// Records `event` on `span`: `kind` is "motion" or "button", and motion
+/// events also get their `period` in milliseconds.
+pub(crate) fn record(span: &Span, event: &Event) {
+    match event {
+        Event::Motion(m) => {
+            span.record("kind", "motion");
+            span.record("period", m.period);
+        }
+        Event::Button(_) => {
+            span.record("kind", "button");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ButtonEvent, Connection, Event, MotionEvent};
+    use std::fmt::Debug;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Metadata, Subscriber};
+
+    /// Collects `(span name, field, value)` for every recorded field.
+    #[derive(Default)]
+    struct Fields {
+        next: AtomicU64,
+        names: Mutex<Vec<&'static str>>,
+        seen: Arc<Mutex<Vec<(String, String, String)>>>,
+    }
+
+    struct Visitor<'a>(&'a str, &'a Mutex<Vec<(String, String, String)>>);
+
+    impl Visit for Visitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            let entry = (self.0.into(), field.name().into(), format!("{:?}", value));
+            self.1.lock().unwrap().push(entry);
+        }
+    }
+
+    impl Subscriber for Fields {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.names.lock().unwrap().push(span.metadata().name());
+            Id::from_u64(self.next.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let name = self.names.lock().unwrap()[span.into_u64() as usize - 1];
+            values.record(&mut Visitor(name, &self.seen));
+        }
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &tracing::Event<'_>) {}
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn spans_record_kind_and_period() {
+        let c = Connection::new_mock([
+            Event::Motion(MotionEvent::new([1, 0, 0, 0, 0, 0], 16)),
+            Event::Button(ButtonEvent {
+                press: true,
+                bnum: 0,
+            }),
+        ]);
+        let fields = Fields::default();
+        let seen = fields.seen.clone();
+        tracing::subscriber::with_default(fields, || {
+            assert!(c.poll().is_some());
+            c.run(|_| {});
+        });
+        let seen = seen.lock().unwrap();
+        let strs: Vec<(&str, &str, &str)> = seen
+            .iter()
+            .map(|(s, f, v)| (s.as_str(), f.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(
+            strs,
+            [
+                ("poll", "kind", "\"motion\""),
+                ("poll", "period", "16"),
+                ("wait", "kind", "\"button\""),
+                ("event", "kind", "\"button\""),
+            ]
+        );
+    }
+}
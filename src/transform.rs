@@ -0,0 +1,516 @@
+use crate::{Connection, Event, MotionEvent, OneEuroFilter};
+
+/// Full-scale deflection, in device counts, assumed unless a
+/// [`FullScale`] says otherwise. Typical of 3Dconnexion devices.
+pub const DEFAULT_FULL_SCALE: f64 = 350.0;
+
+/// The deflection, in device counts, that normalizes to 1.0.
+///
+/// Devices saturate at different counts; pass the same `FullScale` to
+/// every normalizing helper so they agree. A learning full scale starts
+/// from its initial value and grows to the largest magnitude it has
+/// [observed](Self::observe), so a device that saturates higher than
+/// expected is still normalized into `-1.0..=1.0`. It never shrinks.
+///
+/// The value is always positive and finite, so normalizing never yields
+/// infinity or NaN; deserializing any other value fails.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawFullScale"))]
+pub struct FullScale {
+    value: f64,
+    learn: bool,
+}
+
+/// Unchecked form of [`FullScale`] for deserializing.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawFullScale {
+    value: f64,
+    learn: bool,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawFullScale> for FullScale {
+    type Error = String;
+
+    fn try_from(raw: RawFullScale) -> Result<Self, Self::Error> {
+        if raw.value > 0.0 && raw.value.is_finite() {
+            Ok(FullScale {
+                value: raw.value,
+                learn: raw.learn,
+            })
+        } else {
+            Err(format!(
+                "full scale must be positive and finite, not {}",
+                raw.value
+            ))
+        }
+    }
+}
+
+impl Default for FullScale {
+    fn default() -> Self {
+        FullScale::new(DEFAULT_FULL_SCALE)
+    }
+}
+
+impl FullScale {
+    /// # Panics
+    ///
+    /// Panics unless `value` is positive and finite.
+    pub fn new(value: f64) -> Self {
+        Self::check(value);
+        FullScale {
+            value,
+            learn: false,
+        }
+    }
+
+    /// A full scale that learns from observed events, starting at
+    /// `initial`.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless `initial` is positive and finite.
+    pub fn learning(initial: f64) -> Self {
+        Self::check(initial);
+        FullScale {
+            value: initial,
+            learn: true,
+        }
+    }
+
+    fn check(value: f64) {
+        assert!(
+            value > 0.0 && value.is_finite(),
+            "full scale must be positive and finite, not {}",
+            value
+        );
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Updates a learning full scale from `ev`; does nothing otherwise.
+    /// The value only ever grows to a finite peak, so it stays valid.
+    pub fn observe(&mut self, ev: &MotionEvent) {
+        if self.learn {
+            let peak = ev.axes().iter().map(|v| v.unsigned_abs()).max();
+            let peak = peak.unwrap_or(0) as f64;
+            if peak > self.value {
+                self.value = peak;
+            }
+        }
+    }
+
+    pub fn normalize(&self, v: i32) -> f64 {
+        v as f64 / self.value
+    }
+}
+
+impl MotionEvent {
+    /// Translation axes scaled so `fs` counts is 1.0.
+    pub fn t_normalized(&self, fs: &FullScale) -> (f64, f64, f64) {
+        (
+            fs.normalize(self.x),
+            fs.normalize(self.y),
+            fs.normalize(self.z),
+        )
+    }
+
+    /// Rotation axes scaled so `fs` counts is 1.0.
+    pub fn r_normalized(&self, fs: &FullScale) -> (f64, f64, f64) {
+        (
+            fs.normalize(self.rx),
+            fs.normalize(self.ry),
+            fs.normalize(self.rz),
+        )
+    }
+}
+
+/// A stage in a [`Pipeline`]. Returning `None` drops the event.
+pub trait MotionTransform {
+    fn transform(&mut self, ev: MotionEvent) -> Option<MotionEvent>;
+}
+
+/// Runs motion events through a list of transforms in order.
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Box<dyn MotionTransform + Send>>,
+}
+
+impl std::fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("stages", &self.stages.len())
+            .finish()
+    }
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, stage: impl MotionTransform + Send + 'static) -> Self {
+        self.push(stage);
+        self
+    }
+
+    pub fn push(&mut self, stage: impl MotionTransform + Send + 'static) {
+        self.stages.push(Box::new(stage));
+    }
+
+    /// Applies each stage in turn, stopping as soon as one drops the event.
+    pub fn apply(&mut self, ev: MotionEvent) -> Option<MotionEvent> {
+        self.stages.iter_mut().try_fold(ev, |ev, s| s.transform(ev))
+    }
+}
+
+/// Zeroes axes whose magnitude is below a per-axis threshold.
+///
+/// An event that only had motion inside the dead zone is dropped. Events
+/// that were already all-zero pass through, since apps may rely on them to
+/// detect release.
+#[derive(Debug, Clone)]
+pub struct Deadzone {
+    thresholds: [i32; 6],
+}
+
+impl Deadzone {
+    pub fn new(threshold: i32) -> Self {
+        Deadzone {
+            thresholds: [threshold; 6],
+        }
+    }
+
+    pub fn per_axis(thresholds: [i32; 6]) -> Self {
+        Deadzone { thresholds }
+    }
+}
+
+impl MotionTransform for Deadzone {
+    fn transform(&mut self, mut ev: MotionEvent) -> Option<MotionEvent> {
+        if ev.is_zero() {
+            return Some(ev);
+        }
+        for (i, v) in ev.axes().into_iter().enumerate() {
+            if v.abs() < self.thresholds[i] {
+                ev.set_axis(i, 0);
+            }
+        }
+        (!ev.is_zero()).then_some(ev)
+    }
+}
+
+/// Power-law response: `out = sign(v) * fs * (|v| / fs)^exponent`, where
+/// `fs` is the full-scale deflection. Exponents above 1 soften small
+/// movements while keeping full deflection unchanged.
+#[derive(Debug, Clone)]
+pub struct ResponseCurve {
+    exponent: f64,
+    full_scale: f64,
+}
+
+impl ResponseCurve {
+    pub fn new(exponent: f64) -> Self {
+        ResponseCurve {
+            exponent,
+            full_scale: DEFAULT_FULL_SCALE,
+        }
+    }
+
+    pub fn with_full_scale(mut self, fs: FullScale) -> Self {
+        self.full_scale = fs.value();
+        self
+    }
+}
+
+impl MotionTransform for ResponseCurve {
+    fn transform(&mut self, mut ev: MotionEvent) -> Option<MotionEvent> {
+        for (i, v) in ev.axes().into_iter().enumerate() {
+            let n = v as f64 / self.full_scale;
+            let out = n.signum() * n.abs().powf(self.exponent) * self.full_scale;
+            ev.set_axis(i, out.round() as i32);
+        }
+        Some(ev)
+    }
+}
+
+/// Piecewise-linear response curve given as `(input, output)` breakpoints
+/// in device counts.
+///
+/// Inputs between breakpoints are interpolated linearly; inputs outside
+/// them clamp to the first or last output. A curve with no breakpoints
+/// passes values through. As a [`MotionTransform`] it applies to every
+/// axis; use `[AxisCurve; 6]` for a curve per axis.
+///
+/// With the `serde` feature a curve (de)serializes as its list of
+/// breakpoints, e.g. `[[-350,-350],[0,0],[100,20],[350,350]]`.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "Vec<(f64, f64)>", into = "Vec<(f64, f64)>")
+)]
+pub struct AxisCurve {
+    points: Vec<(f64, f64)>,
+}
+
+impl From<Vec<(f64, f64)>> for AxisCurve {
+    fn from(points: Vec<(f64, f64)>) -> Self {
+        AxisCurve::new(points)
+    }
+}
+
+impl From<AxisCurve> for Vec<(f64, f64)> {
+    fn from(curve: AxisCurve) -> Self {
+        curve.points
+    }
+}
+
+impl AxisCurve {
+    /// Breakpoints may be given in any order; they're sorted by input.
+    pub fn new(mut points: Vec<(f64, f64)>) -> Self {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        AxisCurve { points }
+    }
+
+    pub fn points(&self) -> &[(f64, f64)] {
+        &self.points
+    }
+
+    pub fn eval(&self, input: f64) -> f64 {
+        let (Some(&first), Some(&last)) = (self.points.first(), self.points.last()) else {
+            return input;
+        };
+        if input <= first.0 {
+            return first.1;
+        }
+        if input >= last.0 {
+            return last.1;
+        }
+        let i = self.points.partition_point(|p| p.0 < input);
+        let (x0, y0) = self.points[i - 1];
+        let (x1, y1) = self.points[i];
+        y0 + (y1 - y0) * (input - x0) / (x1 - x0)
+    }
+
+    /// Runs every axis of `ev` through the curve.
+    pub fn apply(&self, mut ev: MotionEvent) -> MotionEvent {
+        for (i, v) in ev.axes().into_iter().enumerate() {
+            ev.set_axis(i, self.eval(v as f64).round() as i32);
+        }
+        ev
+    }
+}
+
+impl MotionTransform for AxisCurve {
+    fn transform(&mut self, ev: MotionEvent) -> Option<MotionEvent> {
+        Some(self.apply(ev))
+    }
+}
+
+impl MotionTransform for [AxisCurve; 6] {
+    fn transform(&mut self, mut ev: MotionEvent) -> Option<MotionEvent> {
+        for (i, v) in ev.axes().into_iter().enumerate() {
+            ev.set_axis(i, self[i].eval(v as f64).round() as i32);
+        }
+        Some(ev)
+    }
+}
+
+/// Reorders and inverts axes: output axis `i` is input axis `sources[i]`,
+/// negated if inverted.
+#[derive(Debug, Clone)]
+pub struct AxisMap {
+    sources: [usize; 6],
+    signs: [i32; 6],
+}
+
+impl Default for AxisMap {
+    fn default() -> Self {
+        AxisMap {
+            sources: [0, 1, 2, 3, 4, 5],
+            signs: [1; 6],
+        }
+    }
+}
+
+impl AxisMap {
+    /// # Panics
+    ///
+    /// Panics if any source index is not in `0..6`.
+    pub fn new(sources: [usize; 6]) -> Self {
+        for &s in &sources {
+            check_axis(s);
+        }
+        AxisMap {
+            sources,
+            signs: [1; 6],
+        }
+    }
+
+    /// Swaps output axes `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is not in `0..6`.
+    pub fn swap(mut self, a: usize, b: usize) -> Self {
+        check_axis(a);
+        check_axis(b);
+        self.sources.swap(a, b);
+        self.signs.swap(a, b);
+        self
+    }
+
+    /// Negates output axis `axis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is not in `0..6`.
+    pub fn invert(mut self, axis: usize) -> Self {
+        check_axis(axis);
+        self.signs[axis] = -self.signs[axis];
+        self
+    }
+}
+
+fn check_axis(axis: usize) {
+    assert!(axis < 6, "axis index {} out of range 0..6", axis);
+}
+
+impl MotionTransform for AxisMap {
+    fn transform(&mut self, mut ev: MotionEvent) -> Option<MotionEvent> {
+        let axes = ev.axes();
+        for i in 0..6 {
+            ev.set_axis(i, axes[self.sources[i]].saturating_mul(self.signs[i]));
+        }
+        Some(ev)
+    }
+}
+
+impl MotionTransform for OneEuroFilter {
+    fn transform(&mut self, ev: MotionEvent) -> Option<MotionEvent> {
+        Some(self.apply(&ev))
+    }
+}
+
+impl Connection {
+    /// Polls for the next event, running motion through `p`. Motion the
+    /// pipeline drops is skipped in favour of the next queued event;
+    /// button events pass through untouched.
+    pub fn poll_pipeline(&self, p: &mut Pipeline) -> Option<Event> {
+        loop {
+            match self.poll()? {
+                Event::Motion(m) => {
+                    if let Some(m) = p.apply(m) {
+                        return Some(Event::Motion(m));
+                    }
+                }
+                event => return Some(event),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ButtonEvent, RY, TX, TY, TZ};
+
+    #[test]
+    fn deadzone_then_axis_map() {
+        let mut p = Pipeline::new()
+            .with(Deadzone::new(10))
+            .with(AxisMap::default().swap(TY, TZ).invert(TX));
+        let out = p.apply(MotionEvent::new([5, 20, -30, 0, 9, 0], 8)).unwrap();
+        assert_eq!(out.axes(), [0, -30, 20, 0, 0, 0]);
+        assert!(p.apply(MotionEvent::new([5, 0, 0, 0, 9, 0], 8)).is_none());
+        assert!(p.apply(MotionEvent::new([0; 6], 8)).is_some());
+    }
+
+    #[test]
+    fn response_curve_keeps_full_scale() {
+        let mut c = ResponseCurve::new(2.0);
+        let out = c
+            .transform(MotionEvent::new([350, -175, 0, 0, 0, 0], 8))
+            .unwrap();
+        assert_eq!(out.axes(), [350, -88, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn full_scale_normalization() {
+        let m = MotionEvent::new([35, -70, 350, 0, 0, 0], 8);
+        assert_eq!(m.t_normalized(&FullScale::default()), (0.1, -0.2, 1.0));
+        assert_eq!(m.t_normalized(&FullScale::new(175.0)), (0.2, -0.4, 2.0));
+        let mut fs = FullScale::learning(100.0);
+        fs.observe(&m);
+        assert_eq!(fs.value(), 350.0);
+        fs.observe(&MotionEvent::new([1, 0, 0, 0, 0, 0], 8));
+        assert_eq!(fs.value(), 350.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "axis index 6 out of range 0..6")]
+    fn axis_map_rejects_bad_index() {
+        AxisMap::default().swap(TX, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "full scale must be positive and finite")]
+    fn zero_full_scale_panics() {
+        FullScale::new(0.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn negative_full_scale_does_not_deserialize() {
+        let json = r#"{"value":-1.0,"learn":false}"#;
+        assert!(serde_json::from_str::<FullScale>(json).is_err());
+    }
+
+    #[test]
+    fn axis_curve_interpolates_and_clamps() {
+        let c = AxisCurve::new(vec![(100.0, 20.0), (0.0, 0.0), (300.0, 300.0)]);
+        assert_eq!(c.eval(50.0), 10.0);
+        assert_eq!(c.eval(200.0), 160.0);
+        assert_eq!(c.eval(100.0), 20.0);
+        assert_eq!(c.eval(-40.0), 0.0);
+        assert_eq!(c.eval(350.0), 300.0);
+        let out = c.apply(MotionEvent::new([50, 350, -5, 0, 150, 0], 8));
+        assert_eq!(out.axes(), [10, 300, 0, 0, 90, 0]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn axis_curve_serde_sorts_points() {
+        let c: AxisCurve = serde_json::from_str("[[350,350],[0,0]]").unwrap();
+        assert_eq!(c.points(), [(0.0, 0.0), (350.0, 350.0)]);
+        assert_eq!(
+            serde_json::to_string(&c).unwrap(),
+            "[[0.0,0.0],[350.0,350.0]]"
+        );
+    }
+
+    #[test]
+    fn poll_pipeline_skips_dropped() {
+        let c = Connection::new_mock([
+            Event::Motion(MotionEvent::new([1, 0, 0, 0, 0, 0], 8)),
+            Event::Button(ButtonEvent {
+                press: true,
+                bnum: 0,
+            }),
+            Event::Motion(MotionEvent::new([0, 0, 0, 0, 50, 0], 8)),
+        ]);
+        let mut p = Pipeline::new().with(Deadzone::new(10));
+        assert!(matches!(c.poll_pipeline(&mut p), Some(Event::Button(_))));
+        match c.poll_pipeline(&mut p) {
+            Some(Event::Motion(m)) => assert_eq!(m.axes()[RY], 50),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(c.poll_pipeline(&mut p).is_none());
+    }
+}
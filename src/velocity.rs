@@ -0,0 +1,107 @@
+use crate::MotionEvent;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Smoothed per-axis velocity over the last few motion events.
+///
+/// Each event's values are the cap's deflection, which is itself a rate:
+/// the same model [`Integrator`](crate::Integrator) integrates over time
+/// and [`DeltaTracker`](crate::DeltaTracker) turns into position deltas.
+/// A single event's values are a noisy estimate, so this averages them
+/// over a window, weighting each event after the oldest by the time since
+/// the event before it; the oldest only marks where the window starts.
+/// The result is in the same units as the events, so an `Integrator` with
+/// unit gains moves by about `velocity()` per second.
+///
+/// A larger window averages out more jitter in values and arrival times
+/// but reacts more slowly to real changes: with the device reporting
+/// every ~16 ms, a window of 8 lags by roughly 60 ms. With a single event,
+/// or a window spanning no time, the events' values are averaged with
+/// equal weight.
+#[derive(Debug, Clone)]
+pub struct VelocityEstimator {
+    window: usize,
+    samples: VecDeque<(Instant, [i32; 6])>,
+}
+
+impl VelocityEstimator {
+    /// Panics if `window` is zero.
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "window must hold at least one event");
+        VelocityEstimator {
+            window,
+            samples: VecDeque::with_capacity(window),
+        }
+    }
+
+    pub fn feed(&mut self, ev: &MotionEvent, now: Instant) {
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((now, ev.axes()));
+    }
+
+    /// Zero until an event has been fed.
+    pub fn velocity(&self) -> [f64; 6] {
+        let (Some(first), Some(last)) = (self.samples.front(), self.samples.back()) else {
+            return [0.0; 6];
+        };
+        let span = last.0.saturating_duration_since(first.0).as_secs_f64();
+        let mut sum = [0.0; 6];
+        if span == 0.0 {
+            for (_, axes) in &self.samples {
+                for (s, v) in sum.iter_mut().zip(axes) {
+                    *s += *v as f64;
+                }
+            }
+            let n = self.samples.len() as f64;
+            return sum.map(|s| s / n);
+        }
+        let mut prev = first.0;
+        for (at, axes) in self.samples.iter().skip(1) {
+            let dt = at.saturating_duration_since(prev).as_secs_f64();
+            prev = *at;
+            for (s, v) in sum.iter_mut().zip(axes) {
+                *s += *v as f64 * dt;
+            }
+        }
+        sum.map(|s| s / span)
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn constant_rate() {
+        let t0 = Instant::now();
+        let m = MotionEvent::new([10, 0, -5, 0, 0, 0], 10);
+        let mut v = VelocityEstimator::new(4);
+        assert_eq!(v.velocity(), [0.0; 6]);
+        v.feed(&m, t0);
+        assert_eq!(v.velocity()[0], 10.0);
+        for i in 1..10 {
+            v.feed(&m, t0 + Duration::from_millis(10 * i));
+            let vel = v.velocity();
+            assert!((vel[0] - 10.0).abs() < 1e-9, "{:?}", vel);
+            assert!((vel[2] + 5.0).abs() < 1e-9, "{:?}", vel);
+        }
+    }
+
+    #[test]
+    fn weights_by_arrival_gap() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let mut v = VelocityEstimator::new(3);
+        v.feed(&MotionEvent::new([999, 0, 0, 0, 0, 0], 10), t0);
+        v.feed(&MotionEvent::new([40, 0, 0, 0, 0, 0], 10), t0 + ms(30));
+        v.feed(&MotionEvent::new([0, 0, 0, 0, 0, 0], 10), t0 + ms(40));
+        assert!((v.velocity()[0] - 30.0).abs() < 1e-9, "{:?}", v.velocity());
+    }
+}
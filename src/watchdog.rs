@@ -0,0 +1,81 @@
+use crate::{Connection, Event, SpnavError};
+use std::time::{Duration, Instant};
+
+/// Reconnects a [`Connection`] that has gone quiet for too long.
+///
+/// A hung daemon can leave the connection open but silent. The watchdog
+/// can't tell that apart from a device nobody is touching, which is also
+/// silent, so a reconnect on an idle device is harmless but wasted work.
+/// Choose a timeout that is long compared to normal pauses in use (minutes
+/// rather than seconds), and only wrap connections that need it.
+#[derive(Debug)]
+pub struct Watchdog {
+    conn: Connection,
+    timeout: Duration,
+    last_event: Instant,
+    reconnects: u32,
+}
+
+impl Watchdog {
+    pub fn new(conn: Connection, timeout: Duration) -> Self {
+        Watchdog {
+            conn,
+            timeout,
+            last_event: Instant::now(),
+            reconnects: 0,
+        }
+    }
+
+    /// Polls for an event, reconnecting if none has arrived for the
+    /// timeout as of `now`. A reconnect restarts the silence timer, even
+    /// when it fails, so a missing daemon is retried once per timeout.
+    pub fn poll_watched(&mut self, now: Instant) -> Result<Option<Event>, SpnavError> {
+        if let Some(event) = self.conn.poll() {
+            self.last_event = now;
+            return Ok(Some(event));
+        }
+        if now.saturating_duration_since(self.last_event) < self.timeout {
+            return Ok(None);
+        }
+        self.last_event = now;
+        self.reconnects += 1;
+        self.conn.reconnect().map(|()| None)
+    }
+
+    /// How many reconnects have been attempted.
+    pub fn reconnects(&self) -> u32 {
+        self.reconnects
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    pub fn into_inner(self) -> Connection {
+        self.conn
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ButtonEvent;
+
+    #[test]
+    fn reconnects_after_silence() {
+        let s = Duration::from_secs;
+        let t0 = Instant::now();
+        let conn = Connection::new_mock([Event::Button(ButtonEvent {
+            press: true,
+            bnum: 0,
+        })]);
+        let mut w = Watchdog::new(conn, s(60));
+        assert!(w.poll_watched(t0).unwrap().is_some());
+        assert!(w.poll_watched(t0 + s(59)).unwrap().is_none());
+        assert_eq!(w.reconnects(), 0);
+        assert!(w.poll_watched(t0 + s(60)).unwrap().is_none());
+        assert_eq!(w.reconnects(), 1);
+        assert!(w.poll_watched(t0 + s(119)).unwrap().is_none());
+        assert_eq!(w.reconnects(), 1);
+    }
+}